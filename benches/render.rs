@@ -0,0 +1,67 @@
+// Times the render loop on the bundled scenes at a fixed size, sample count and seed, on one
+// thread so the numbers are comparable between machines with different core counts.
+//
+//     cargo bench --bench render [-- <scene name filter>] 2>/dev/null
+//
+// Add `--features f32` to time the single precision build. To compare two revisions, run the
+// same command on each, copying this file and its `[[bench]]` entry into revisions that predate
+// it; the fixed seed makes both render exactly the same paths. Expect a few percent of noise
+// between runs, so compare the best times.
+
+use raytracer::{load_scene, BvhNode, Camera, RenderSettings};
+use std::env;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const SCENES: [&str; 2] = ["scenes/cornell_box.json", "scenes/three_spheres.json"];
+const IMAGE_WIDTH: u32 = 300;
+const IMAGE_HEIGHT: u32 = 200;
+const SAMPLES_PER_PIXEL: u32 = 32;
+const SEED: u64 = 7;
+const RUNS: usize = 5;
+
+fn main() {
+    // Cargo passes `--bench` to benchmarks without the default harness.
+    let filters: Vec<String> = env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-'))
+        .collect();
+
+    println!(
+        "{}x{}, {} spp, seed {}, 1 thread, {} runs",
+        IMAGE_WIDTH, IMAGE_HEIGHT, SAMPLES_PER_PIXEL, SEED, RUNS
+    );
+    for scene_path in SCENES {
+        if !filters.is_empty() && !filters.iter().any(|filter| scene_path.contains(filter)) {
+            continue;
+        }
+
+        let mut timings: Vec<Duration> = (0..RUNS).map(|_| render(scene_path)).collect();
+        timings.sort();
+        println!(
+            "{:<28} best {:>7.3} s  median {:>7.3} s",
+            scene_path,
+            timings[0].as_secs_f64(),
+            timings[RUNS / 2].as_secs_f64()
+        );
+    }
+}
+
+// Scene loading and BVH construction are excluded from the timing.
+fn render(scene_path: &str) -> Duration {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(scene_path);
+    let scene = load_scene(&path).unwrap_or_else(|error| panic!("{}: {}", scene_path, error));
+    let settings = RenderSettings::new(
+        IMAGE_WIDTH,
+        IMAGE_HEIGHT,
+        SAMPLES_PER_PIXEL,
+        scene.render.max_depth(),
+    )
+    .unwrap();
+    let camera = Camera::new(settings, scene.pose, scene.lens, scene.environment).unwrap();
+    let world = BvhNode::new(scene.world);
+
+    let start = Instant::now();
+    camera.render(&world, 1, Some(SEED), |_, _| {});
+    start.elapsed()
+}
@@ -0,0 +1,162 @@
+use crate::float::{Float, ROUNDING_TOLERANCE};
+use crate::interval::{Interval, EMPTY};
+use crate::point::Point3;
+use crate::ray::Ray;
+
+const MINIMUM_SIZE: Float = 0.0001;
+
+#[derive(Clone)]
+pub struct Aabb {
+    pub x: Interval,
+    pub y: Interval,
+    pub z: Interval,
+}
+
+impl Aabb {
+    pub fn new(x: Interval, y: Interval, z: Interval) -> Self {
+        // Flat primitives such as axis-aligned triangles would give zero-thickness slabs that
+        // the hit test can never pass, so every side is padded to a minimum size.
+        Self {
+            x: pad_to_minimum(x),
+            y: pad_to_minimum(y),
+            z: pad_to_minimum(z),
+        }
+    }
+
+    pub fn from_points(a: Point3, b: Point3) -> Self {
+        Self::new(
+            Interval::new(a.x.min(b.x), a.x.max(b.x)),
+            Interval::new(a.y.min(b.y), a.y.max(b.y)),
+            Interval::new(a.z.min(b.z), a.z.max(b.z)),
+        )
+    }
+
+    pub fn surrounding(a: &Aabb, b: &Aabb) -> Self {
+        Self::new(
+            Interval::enclosing(&a.x, &b.x),
+            Interval::enclosing(&a.y, &b.y),
+            Interval::enclosing(&a.z, &b.z),
+        )
+    }
+
+    pub fn axis_interval(&self, axis: usize) -> &Interval {
+        match axis {
+            1 => &self.y,
+            2 => &self.z,
+            _ => &self.x,
+        }
+    }
+
+    pub fn longest_axis(&self) -> usize {
+        if self.x.size() > self.y.size() {
+            if self.x.size() > self.z.size() {
+                0
+            } else {
+                2
+            }
+        } else if self.y.size() > self.z.size() {
+            1
+        } else {
+            2
+        }
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        Point3::new(
+            (self.x.min + self.x.max) / 2.0,
+            (self.y.min + self.y.max) / 2.0,
+            (self.z.min + self.z.max) / 2.0,
+        )
+    }
+
+    pub fn surface_area(&self) -> Float {
+        let x = self.x.size().max(0.0);
+        let y = self.y.size().max(0.0);
+        let z = self.z.size().max(0.0);
+        2.0 * (x * y + y * z + z * x)
+    }
+
+    pub fn hit(&self, ray: &Ray, ray_t: &Interval) -> bool {
+        let mut t_min = ray_t.min;
+        let mut t_max = ray_t.max;
+
+        for axis in 0..3 {
+            let interval = self.axis_interval(axis);
+            let inverse_direction = 1.0 / ray.direction[axis];
+
+            let t0 = (interval.min - ray.origin[axis]) * inverse_direction;
+            let t1 = (interval.max - ray.origin[axis]) * inverse_direction;
+
+            t_min = t_min.max(t0.min(t1));
+            t_max = t_max.min(t0.max(t1));
+
+            if t_max <= t_min {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl Default for Aabb {
+    fn default() -> Self {
+        EMPTY_BOX
+    }
+}
+
+pub const EMPTY_BOX: Aabb = Aabb {
+    x: EMPTY,
+    y: EMPTY,
+    z: EMPTY,
+};
+
+// Far from the origin the padding has to grow with the coordinates, or rounding in the hit test
+// collapses the slab again.
+fn pad_to_minimum(interval: Interval) -> Interval {
+    let magnitude = interval.min.abs().max(interval.max.abs());
+    let minimum_size = (ROUNDING_TOLERANCE * magnitude).max(MINIMUM_SIZE);
+    if (0.0..minimum_size).contains(&interval.size()) {
+        interval.expand(minimum_size)
+    } else {
+        interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::Vector3;
+
+    #[test]
+    fn pads_flat_boxes() {
+        let flat = Aabb::from_points(Point3::new(0.0, 0.0, 1.0), Point3::new(1.0, 1.0, 1.0));
+        assert!(flat.z.size() > 0.99 * MINIMUM_SIZE);
+        assert_eq!(flat.x.size(), 1.0);
+    }
+
+    #[test]
+    fn hits_flat_boxes_far_from_the_origin() {
+        for z in [1.0, 555.0, 1e5] {
+            let flat = Aabb::from_points(Point3::new(0.0, 0.0, z), Point3::new(555.0, 555.0, z));
+            let ray = Ray::new(
+                Point3::new(278.0, 278.0, 0.0),
+                Vector3::new(1e-6, -2e-6, 1.0),
+            );
+            assert!(
+                flat.hit(&ray, &Interval::new(0.001, Float::INFINITY)),
+                "missed wall at {z}"
+            );
+        }
+    }
+
+    #[test]
+    fn misses_boxes_off_the_ray() {
+        let bbox = Aabb::from_points(Point3::new(1.0, 1.0, 1.0), Point3::new(2.0, 2.0, 2.0));
+        let ray = Ray::new(Point3::origin(), Vector3::new(1.0, -1.0, 1.0));
+        assert!(!bbox.hit(&ray, &Interval::new(0.0, Float::INFINITY)));
+        let ray = Ray::new(Point3::origin(), Vector3::new(1.0, 1.0, 1.0));
+        assert!(bbox.hit(&ray, &Interval::new(0.0, Float::INFINITY)));
+        assert!(!bbox.hit(&ray, &Interval::new(0.0, 0.5)));
+    }
+}
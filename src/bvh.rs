@@ -0,0 +1,149 @@
+use crate::aabb::{Aabb, EMPTY_BOX};
+use crate::float::Float;
+use crate::hit::{HitRecord, Hittable, HittableList};
+use crate::interval::Interval;
+use crate::ray::Ray;
+
+// Relative cost of visiting a node compared to intersecting a single object.
+const TRAVERSAL_COST: Float = 0.125;
+const MAX_LEAF_OBJECTS: usize = 4;
+
+pub struct BvhNode {
+    bbox: Aabb,
+    content: BvhContent,
+}
+
+enum BvhContent {
+    Leaf(Vec<Box<dyn Hittable>>),
+    Branch(Box<BvhNode>, Box<BvhNode>),
+}
+
+struct Split {
+    axis: usize,
+    index: usize,
+    cost: Float,
+}
+
+impl BvhNode {
+    pub fn new(list: HittableList) -> Self {
+        Self::build(list.into_objects())
+    }
+
+    fn build(mut objects: Vec<Box<dyn Hittable>>) -> Self {
+        let bbox = objects.iter().fold(EMPTY_BOX, |bbox, object| {
+            Aabb::surrounding(&bbox, &object.bounding_box())
+        });
+
+        if objects.len() <= 1 {
+            return Self {
+                bbox,
+                content: BvhContent::Leaf(objects),
+            };
+        }
+
+        let split = best_split(&mut objects, &bbox);
+        let leaf_cost = objects.len() as Float;
+        if objects.len() <= MAX_LEAF_OBJECTS && leaf_cost <= split.cost {
+            return Self {
+                bbox,
+                content: BvhContent::Leaf(objects),
+            };
+        }
+
+        sort_by_centroid(&mut objects, split.axis);
+        let right = objects.split_off(split.index);
+
+        Self {
+            bbox,
+            content: BvhContent::Branch(
+                Box::new(Self::build(objects)),
+                Box::new(Self::build(right)),
+            ),
+        }
+    }
+}
+
+// Evaluates the surface area heuristic for every split position along every axis.
+fn best_split(objects: &mut [Box<dyn Hittable>], bbox: &Aabb) -> Split {
+    let count = objects.len();
+    let parent_area = bbox.surface_area();
+    let mut best = Split {
+        axis: bbox.longest_axis(),
+        index: count / 2,
+        cost: Float::INFINITY,
+    };
+
+    if parent_area <= 0.0 || !parent_area.is_finite() {
+        return best;
+    }
+
+    for axis in 0..3 {
+        sort_by_centroid(objects, axis);
+
+        let mut right_areas = vec![0.0; count];
+        let mut right_bbox = EMPTY_BOX;
+        for i in (1..count).rev() {
+            right_bbox = Aabb::surrounding(&right_bbox, &objects[i].bounding_box());
+            right_areas[i] = right_bbox.surface_area();
+        }
+
+        let mut left_bbox = EMPTY_BOX;
+        for i in 1..count {
+            left_bbox = Aabb::surrounding(&left_bbox, &objects[i - 1].bounding_box());
+            let cost = TRAVERSAL_COST
+                + (left_bbox.surface_area() * i as Float + right_areas[i] * (count - i) as Float)
+                    / parent_area;
+            if cost < best.cost {
+                best = Split {
+                    axis,
+                    index: i,
+                    cost,
+                };
+            }
+        }
+    }
+
+    best
+}
+
+fn sort_by_centroid(objects: &mut [Box<dyn Hittable>], axis: usize) {
+    objects.sort_by(|a, b| {
+        let a = a.bounding_box().centroid()[axis];
+        let b = b.bounding_box().centroid()[axis];
+        a.total_cmp(&b)
+    });
+}
+
+impl Hittable for BvhNode {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        if !self.bbox.hit(ray, &ray_t) {
+            return None;
+        }
+
+        match &self.content {
+            BvhContent::Leaf(objects) => {
+                let mut closest_hit_record: Option<HitRecord> = None;
+                let mut closest_so_far = ray_t.max;
+
+                for object in objects {
+                    if let Some(hit) = object.hit(ray, Interval::new(ray_t.min, closest_so_far)) {
+                        closest_so_far = hit.t;
+                        closest_hit_record = Some(hit);
+                    }
+                }
+
+                closest_hit_record
+            }
+            BvhContent::Branch(left, right) => {
+                let hit_left = left.hit(ray, ray_t.clone());
+                let closest_so_far = hit_left.as_ref().map_or(ray_t.max, |hit| hit.t);
+                let hit_right = right.hit(ray, Interval::new(ray_t.min, closest_so_far));
+                hit_right.or(hit_left)
+            }
+        }
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox.clone()
+    }
+}
@@ -1,97 +1,540 @@
-use crate::hit::Hittable;
+use crate::color::Color;
+use crate::environment::{Environment, Gradient};
+use crate::float::Float;
+use crate::hit::{HitRecord, Hittable};
+use crate::image::Image;
 use crate::interval::Interval;
+use crate::point::Point3;
 use crate::ray::Ray;
-use crate::utility::{random_from_range, CLOSEST_TO_ZERO_TO_ONE_RANGE};
-use crate::vector::{unit_vector, Vector3};
+use crate::utility::{
+    degrees_to_radians, random_from_range, seed_thread_rng, CLOSEST_TO_ZERO_TO_ONE_RANGE,
+};
+use crate::vector::{cross, random_in_unit_disk, unit_vector, Vector3};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+const TILE_SIZE: usize = 16;
+const TILE_SEED_MULTIPLIER: u64 = 0x9e37_79b9_7f4a_7c15;
+
+pub struct CameraPose {
+    pub look_from: Point3,
+    pub look_at: Point3,
+    pub view_up: Vector3,
+    pub vertical_fov: Float,
+}
+
+impl CameraPose {
+    pub fn new(look_from: Point3, look_at: Point3, view_up: Vector3, vertical_fov: Float) -> Self {
+        Self {
+            look_from,
+            look_at,
+            view_up,
+            vertical_fov,
+        }
+    }
+
+    // Checks that the pose has a view direction and that view_up is not parallel to it, as the
+    // camera could not be oriented otherwise.
+    pub fn validate(&self) -> Result<(), CameraPoseError> {
+        let view_direction = self.look_at - self.look_from;
+        if view_direction.is_near_zero() {
+            Err(CameraPoseError::NoViewDirection)
+        } else if self.view_up.is_near_zero()
+            || cross(unit_vector(view_direction), unit_vector(self.view_up)).is_near_zero()
+        {
+            Err(CameraPoseError::ViewUpParallelToViewDirection)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Default for CameraPose {
+    fn default() -> Self {
+        Self::new(
+            Point3::origin(),
+            Point3::new(0.0, 0.0, -1.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            90.0,
+        )
+    }
+}
+
+pub struct CameraLens {
+    pub defocus_angle: Float,
+    pub focus_distance: Float,
+}
+
+impl CameraLens {
+    pub fn new(defocus_angle: Float, focus_distance: Float) -> Self {
+        Self {
+            defocus_angle,
+            focus_distance,
+        }
+    }
+}
+
+impl Default for CameraLens {
+    fn default() -> Self {
+        Self::new(0.0, 10.0)
+    }
+}
+
+#[derive(Debug)]
+pub enum CameraPoseError {
+    NoViewDirection,
+    ViewUpParallelToViewDirection,
+}
+
+impl Display for CameraPoseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            CameraPoseError::NoViewDirection => {
+                write!(f, "look_from and look_at must be different points")
+            }
+            CameraPoseError::ViewUpParallelToViewDirection => {
+                write!(f, "view_up must not be parallel to the view direction")
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RenderSettingsError {
+    ZeroImageWidth,
+    ZeroImageHeight,
+    ZeroSamplesPerPixel,
+    ZeroMaxDepth,
+    InvalidAspectRatio(Float),
+}
+
+impl Display for RenderSettingsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            RenderSettingsError::ZeroImageWidth => write!(f, "image width must be at least 1"),
+            RenderSettingsError::ZeroImageHeight => write!(f, "image height must be at least 1"),
+            RenderSettingsError::ZeroSamplesPerPixel => {
+                write!(f, "samples per pixel must be at least 1")
+            }
+            RenderSettingsError::ZeroMaxDepth => write!(f, "maximum depth must be at least 1"),
+            RenderSettingsError::InvalidAspectRatio(aspect_ratio) => write!(
+                f,
+                "aspect ratio must be a positive number but is {}",
+                aspect_ratio
+            ),
+        }
+    }
+}
+
+pub struct RenderSettings {
+    image_width: u32,
+    image_height: u32,
+    samples_per_pixel: u32,
+    max_depth: u32,
+}
+
+impl RenderSettings {
+    pub fn new(
+        image_width: u32,
+        image_height: u32,
+        samples_per_pixel: u32,
+        max_depth: u32,
+    ) -> Result<Self, RenderSettingsError> {
+        if image_width == 0 {
+            Err(RenderSettingsError::ZeroImageWidth)
+        } else if image_height == 0 {
+            Err(RenderSettingsError::ZeroImageHeight)
+        } else if samples_per_pixel == 0 {
+            Err(RenderSettingsError::ZeroSamplesPerPixel)
+        } else if max_depth == 0 {
+            Err(RenderSettingsError::ZeroMaxDepth)
+        } else {
+            Ok(Self {
+                image_width,
+                image_height,
+                samples_per_pixel,
+                max_depth,
+            })
+        }
+    }
+
+    // Derives the image height from the width, rounded to the nearest whole pixel.
+    pub fn from_aspect_ratio(
+        image_width: u32,
+        aspect_ratio: Float,
+        samples_per_pixel: u32,
+        max_depth: u32,
+    ) -> Result<Self, RenderSettingsError> {
+        if !(aspect_ratio.is_finite() && aspect_ratio > 0.0) {
+            return Err(RenderSettingsError::InvalidAspectRatio(aspect_ratio));
+        }
+        let image_height = (image_width as Float / aspect_ratio).round().max(1.0) as u32;
+        Self::new(image_width, image_height, samples_per_pixel, max_depth)
+    }
+
+    pub fn image_width(&self) -> u32 {
+        self.image_width
+    }
+
+    pub fn image_height(&self) -> u32 {
+        self.image_height
+    }
+
+    pub fn samples_per_pixel(&self) -> u32 {
+        self.samples_per_pixel
+    }
+
+    pub fn max_depth(&self) -> u32 {
+        self.max_depth
+    }
+
+    pub fn aspect_ratio(&self) -> Float {
+        self.image_width as Float / self.image_height as Float
+    }
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            image_width: 400,
+            image_height: 225,
+            samples_per_pixel: 100,
+            max_depth: 50,
+        }
+    }
+}
 
 pub struct Camera {
-    aspect_ratio: f64,
-    image_width: f64,
-    image_height: f64,
-    pixel_samples_scale: f64,
-    max_depth: usize,
-    samples_per_pixel: u8,
-    center: Vector3,
-    first_pixel_coordinate: Vector3,
+    image_width: usize,
+    image_height: usize,
+    pixel_samples_scale: Float,
+    max_depth: u32,
+    samples_per_pixel: u32,
+    center: Point3,
+    first_pixel_coordinate: Point3,
     pixel_delta_u: Vector3,
     pixel_delta_v: Vector3,
+    defocus_angle: Float,
+    defocus_disk_u: Vector3,
+    defocus_disk_v: Vector3,
+    environment: Box<dyn Environment>,
 }
 
 impl Camera {
     pub fn new(
-        aspect_ratio: f64,
-        image_width: u16,
-        samples_per_pixel: u8,
-        max_depth: usize,
-    ) -> Self {
-        let image_width = image_width as f64;
-        let image_height = if image_width / aspect_ratio < 1.0 {
-            1.0
-        } else {
-            image_width / aspect_ratio
-        };
+        settings: RenderSettings,
+        pose: CameraPose,
+        lens: CameraLens,
+        environment: Box<dyn Environment>,
+    ) -> Result<Self, CameraPoseError> {
+        pose.validate()?;
 
-        let pixel_samples_scale = 1.0 / samples_per_pixel as f64;
+        let image_width = settings.image_width as Float;
+        let image_height = settings.image_height as Float;
 
-        let focal_length = 1.0;
-        let viewport_height = 2.0;
+        let pixel_samples_scale = 1.0 / settings.samples_per_pixel as Float;
+
+        let center = pose.look_from;
+        let theta = degrees_to_radians(pose.vertical_fov);
+        let h = (theta / 2.0).tan();
+        let viewport_height = 2.0 * h * lens.focus_distance;
         let viewport_width = viewport_height * (image_width / image_height);
-        let center = Vector3::zero();
 
-        let viewport_u = Vector3::new(viewport_width, 0.0, 0.0);
-        let viewport_v = Vector3::new(0.0, -viewport_height, 0.0);
+        let w = unit_vector(pose.look_from - pose.look_at);
+        let u = unit_vector(cross(pose.view_up, w));
+        let v = cross(w, u);
+
+        let viewport_u = viewport_width * u;
+        let viewport_v = -viewport_height * v;
 
-        let pixel_delta_u = &viewport_u / image_width;
-        let pixel_delta_v = &viewport_v / image_height;
+        let pixel_delta_u = viewport_u / image_width;
+        let pixel_delta_v = viewport_v / image_height;
 
         let viewport_upper_left =
-            &center - Vector3::new(0.0, 0.0, focal_length) - viewport_u / 2.0 - viewport_v / 2.0;
-        let first_pixel_coordinate = viewport_upper_left + 0.5 * (&pixel_delta_u + &pixel_delta_v);
+            center - lens.focus_distance * w - viewport_u / 2.0 - viewport_v / 2.0;
+        let first_pixel_coordinate = viewport_upper_left + 0.5 * (pixel_delta_u + pixel_delta_v);
 
-        Self {
-            aspect_ratio,
-            image_width,
-            image_height,
+        let defocus_radius =
+            lens.focus_distance * degrees_to_radians(lens.defocus_angle / 2.0).tan();
+        let defocus_disk_u = defocus_radius * u;
+        let defocus_disk_v = defocus_radius * v;
+
+        Ok(Self {
+            image_width: settings.image_width as usize,
+            image_height: settings.image_height as usize,
             pixel_samples_scale,
-            samples_per_pixel,
-            max_depth,
+            samples_per_pixel: settings.samples_per_pixel,
+            max_depth: settings.max_depth,
             center,
             first_pixel_coordinate,
             pixel_delta_u,
             pixel_delta_v,
-        }
+            defocus_angle: lens.defocus_angle,
+            defocus_disk_u,
+            defocus_disk_v,
+            environment,
+        })
+    }
+
+    pub fn builder() -> CameraBuilder {
+        CameraBuilder::default()
     }
 
-    pub fn render(&self, world: &dyn Hittable) {
-        println!("P3\n{} {}\n255\n", self.image_width, self.image_height);
+    // With a seed every tile draws from its own deterministic random sequence, so the image does
+    // not depend on the thread count or on the order in which tiles are picked up. `progress` is
+    // called with the number of finished tiles and the total after every tile.
+    pub fn render(
+        &self,
+        world: &dyn Hittable,
+        thread_count: usize,
+        seed: Option<u64>,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Image {
+        let width = self.image_width;
+        let height = self.image_height;
+        let tiles = Tile::split(width, height);
+        let next_tile = AtomicUsize::new(0);
+        let mut image = Image::new(width, height);
 
-        for y in 0..self.image_height as usize {
-            let u = y as f64;
-            eprint!("\rScan lines remaining: {} ", y);
-            for x in 0..self.image_width as usize {
-                let v = x as f64;
-                let mut pixel_color = Vector3::zero();
+        thread::scope(|scope| {
+            let (sender, receiver) = mpsc::channel();
+
+            for _ in 0..thread_count.max(1) {
+                let sender = sender.clone();
+                let tiles = &tiles;
+                let next_tile = &next_tile;
+                scope.spawn(move || loop {
+                    let index = next_tile.fetch_add(1, Ordering::Relaxed);
+                    let Some(tile) = tiles.get(index) else {
+                        break;
+                    };
+                    if let Some(seed) = seed {
+                        seed_thread_rng(seed.wrapping_mul(TILE_SEED_MULTIPLIER) ^ index as u64);
+                    }
+                    let tile_pixels = self.render_tile(tile, world);
+                    if sender.send((tile, tile_pixels)).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(sender);
+
+            for (finished, (tile, tile_pixels)) in (1..).zip(receiver) {
+                let mut tile_pixels = tile_pixels.into_iter();
+                for y in tile.y_start..tile.y_end {
+                    for x in tile.x_start..tile.x_end {
+                        if let Some(color) = tile_pixels.next() {
+                            image.set_pixel(x, y, color);
+                        }
+                    }
+                }
+                progress(finished, tiles.len());
+            }
+        });
+
+        image
+    }
+
+    fn render_tile(&self, tile: &Tile, world: &dyn Hittable) -> Vec<Color> {
+        let mut tile_pixels = Vec::with_capacity(tile.pixel_count());
+        for y in tile.y_start..tile.y_end {
+            let u = y as Float;
+            for x in tile.x_start..tile.x_end {
+                let v = x as Float;
+                let mut pixel_color = Color::black();
                 for _sample in 0..self.samples_per_pixel {
                     let ray = self.get_ray(u, v);
-                    pixel_color += ray_color(ray, self.max_depth, world);
+                    pixel_color += self.ray_color(ray, self.max_depth, world, None);
                 }
-                write_color(self.pixel_samples_scale * pixel_color);
+                tile_pixels.push(self.pixel_samples_scale * pixel_color);
             }
         }
-
-        eprintln!("\rDone.                 \n");
+        tile_pixels
     }
 
-    fn get_ray(&self, u: f64, v: f64) -> Ray {
+    fn get_ray(&self, u: Float, v: Float) -> Ray {
         let offset = sample_square();
-        let pixel_sample = &self.first_pixel_coordinate
-            + ((v + offset.x) * &self.pixel_delta_u)
-            + ((u + offset.y) * &self.pixel_delta_v);
-        let ray_origin = self.center.clone();
-        let ray_direction = pixel_sample - &ray_origin;
+        let pixel_sample = self.first_pixel_coordinate
+            + ((v + offset.x) * self.pixel_delta_u)
+            + ((u + offset.y) * self.pixel_delta_v);
+        let ray_origin = if self.defocus_angle <= 0.0 {
+            self.center
+        } else {
+            self.defocus_disk_sample()
+        };
+        let ray_direction = pixel_sample - ray_origin;
 
         Ray::new(ray_origin, ray_direction)
     }
+
+    fn defocus_disk_sample(&self) -> Point3 {
+        let p = random_in_unit_disk();
+        self.center + (p.x * self.defocus_disk_u) + (p.y * self.defocus_disk_v)
+    }
+
+    fn ray_color(
+        &self,
+        ray: Ray,
+        max_depth: u32,
+        world: &dyn Hittable,
+        scattering_pdf: Option<Float>,
+    ) -> Color {
+        if max_depth == 0 {
+            Color::black()
+        } else if let Some(hit_record) =
+            world.hit(&ray, Interval::new(ray.min_t(), Float::INFINITY))
+        {
+            let emitted = hit_record.material.emitted(&hit_record);
+            if let Some(scatter_result) = hit_record.material.scatter(&ray, &hit_record) {
+                let direct = self.sample_environment(&hit_record, scatter_result.attenuated, world);
+                let pdf = hit_record
+                    .material
+                    .scattering_pdf(&hit_record, scatter_result.scattered.direction);
+                let next_pdf = if pdf > 0.0 { Some(pdf) } else { None };
+
+                emitted
+                    + direct
+                    + scatter_result.attenuated
+                        * self.ray_color(scatter_result.scattered, max_depth - 1, world, next_pdf)
+            } else {
+                emitted
+            }
+        } else {
+            let color = self.environment.color(ray.direction);
+            match scattering_pdf {
+                Some(pdf) => power_heuristic(pdf, self.environment.pdf(ray.direction)) * color,
+                None => color,
+            }
+        }
+    }
+
+    // Next event estimation towards the environment, weighted against the chance of reaching the
+    // same direction by scattering.
+    fn sample_environment(
+        &self,
+        hit_record: &HitRecord,
+        attenuation: Color,
+        world: &dyn Hittable,
+    ) -> Color {
+        let Some(sample) = self.environment.sample() else {
+            return Color::black();
+        };
+
+        let scattering_pdf = hit_record
+            .material
+            .scattering_pdf(hit_record, sample.direction);
+        if scattering_pdf <= 0.0 {
+            return Color::black();
+        }
+
+        let shadow_ray = Ray::new(hit_record.point, sample.direction);
+        if world
+            .hit(
+                &shadow_ray,
+                Interval::new(shadow_ray.min_t(), Float::INFINITY),
+            )
+            .is_some()
+        {
+            return Color::black();
+        }
+
+        let weight = power_heuristic(sample.pdf, scattering_pdf);
+        (weight * scattering_pdf / sample.pdf) * (attenuation * sample.radiance)
+    }
+}
+
+pub struct CameraBuilder {
+    settings: RenderSettings,
+    pose: CameraPose,
+    lens: CameraLens,
+    environment: Box<dyn Environment>,
+}
+
+impl CameraBuilder {
+    pub fn settings(mut self, settings: RenderSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    pub fn pose(mut self, pose: CameraPose) -> Self {
+        self.pose = pose;
+        self
+    }
+
+    pub fn look_from(mut self, look_from: Point3) -> Self {
+        self.pose.look_from = look_from;
+        self
+    }
+
+    pub fn look_at(mut self, look_at: Point3) -> Self {
+        self.pose.look_at = look_at;
+        self
+    }
+
+    pub fn view_up(mut self, view_up: Vector3) -> Self {
+        self.pose.view_up = view_up;
+        self
+    }
+
+    pub fn vertical_fov(mut self, vertical_fov: Float) -> Self {
+        self.pose.vertical_fov = vertical_fov;
+        self
+    }
+
+    pub fn lens(mut self, lens: CameraLens) -> Self {
+        self.lens = lens;
+        self
+    }
+
+    pub fn environment(mut self, environment: Box<dyn Environment>) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    pub fn build(self) -> Result<Camera, CameraPoseError> {
+        Camera::new(self.settings, self.pose, self.lens, self.environment)
+    }
+}
+
+impl Default for CameraBuilder {
+    fn default() -> Self {
+        Self {
+            settings: RenderSettings::default(),
+            pose: CameraPose::default(),
+            lens: CameraLens::default(),
+            environment: Box::new(Gradient::default()),
+        }
+    }
+}
+
+struct Tile {
+    x_start: usize,
+    x_end: usize,
+    y_start: usize,
+    y_end: usize,
+}
+
+impl Tile {
+    fn split(width: usize, height: usize) -> Vec<Tile> {
+        let mut tiles = vec![];
+        for y_start in (0..height).step_by(TILE_SIZE) {
+            for x_start in (0..width).step_by(TILE_SIZE) {
+                tiles.push(Tile {
+                    x_start,
+                    x_end: (x_start + TILE_SIZE).min(width),
+                    y_start,
+                    y_end: (y_start + TILE_SIZE).min(height),
+                });
+            }
+        }
+        tiles
+    }
+
+    fn pixel_count(&self) -> usize {
+        (self.x_end - self.x_start) * (self.y_end - self.y_start)
+    }
 }
 
 fn sample_square() -> Vector3 {
@@ -102,39 +545,100 @@ fn sample_square() -> Vector3 {
     Vector3::new(x, y, z)
 }
 
-fn ray_color(ray: Ray, max_depth: usize, world: &dyn Hittable) -> Vector3 {
-    if max_depth == 0 {
-        Vector3::zero()
-    } else if let Some(hit_record) = world.hit(&ray, Interval::new(0.001, f64::INFINITY)) {
-        if let Some(scatter_result) = hit_record.material.scatter(hit_record) {
-            scatter_result.attenuated * ray_color(scatter_result.scattered, max_depth - 1, world)
-        } else {
-            Vector3::zero()
-        }
+fn power_heuristic(pdf: Float, other_pdf: Float) -> Float {
+    let pdf_squared = pdf * pdf;
+    let other_pdf_squared = other_pdf * other_pdf;
+    if pdf_squared + other_pdf_squared > 0.0 {
+        pdf_squared / (pdf_squared + other_pdf_squared)
     } else {
-        let unit_direction = unit_vector(&ray.direction);
-        let a = 0.5 * (unit_direction.y + 1.0);
-        (1.0 - a) * Vector3::one() + a * Vector3::new(0.5, 0.7, 1.0)
+        0.0
     }
 }
 
-fn linear_to_gamma(linear_component: f64) -> f64 {
-    if linear_component > 0.0 {
-        linear_component.sqrt()
-    } else {
-        0.0
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn height_for(image_width: u32, aspect_ratio: Float) -> u32 {
+        match RenderSettings::from_aspect_ratio(image_width, aspect_ratio, 1, 1) {
+            Ok(settings) => settings.image_height(),
+            Err(error) => panic!("unexpected error: {error}"),
+        }
     }
-}
 
-fn write_color(color: Vector3) {
-    let r = linear_to_gamma(color.x);
-    let g = linear_to_gamma(color.y);
-    let b = linear_to_gamma(color.z);
+    #[test]
+    fn rounds_derived_height_to_nearest_pixel() {
+        assert_eq!(height_for(400, 16.0 / 9.0), 225);
+        // 100 / 1.5 = 66.67 rounds up rather than truncating to 66.
+        assert_eq!(height_for(100, 1.5), 67);
+        assert_eq!(height_for(101, 2.0), 51);
+        assert_eq!(height_for(99, 2.0), 50);
+    }
 
-    let intensity = Interval::new(0.0, 0.999);
-    let r_byte: u8 = (256.0 * intensity.clamp(r)) as u8;
-    let g_byte: u8 = (256.0 * intensity.clamp(g)) as u8;
-    let b_byte: u8 = (256.0 * intensity.clamp(b)) as u8;
+    #[test]
+    fn derived_height_is_at_least_one_pixel() {
+        assert_eq!(height_for(10, 1000.0), 1);
+    }
 
-    println!("{} {} {}\n", r_byte, g_byte, b_byte);
+    #[test]
+    fn rejects_invalid_aspect_ratios() {
+        for aspect_ratio in [0.0, -1.0, Float::NAN, Float::INFINITY] {
+            assert!(matches!(
+                RenderSettings::from_aspect_ratio(100, aspect_ratio, 1, 1),
+                Err(RenderSettingsError::InvalidAspectRatio(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_zero_settings() {
+        assert!(matches!(
+            RenderSettings::new(0, 1, 1, 1),
+            Err(RenderSettingsError::ZeroImageWidth)
+        ));
+        assert!(matches!(
+            RenderSettings::new(1, 0, 1, 1),
+            Err(RenderSettingsError::ZeroImageHeight)
+        ));
+        assert!(matches!(
+            RenderSettings::new(1, 1, 0, 1),
+            Err(RenderSettingsError::ZeroSamplesPerPixel)
+        ));
+        assert!(matches!(
+            RenderSettings::new(1, 1, 1, 0),
+            Err(RenderSettingsError::ZeroMaxDepth)
+        ));
+        assert!(matches!(
+            RenderSettings::from_aspect_ratio(0, 1.0, 1, 1),
+            Err(RenderSettingsError::ZeroImageWidth)
+        ));
+    }
+
+    #[test]
+    fn rejects_degenerate_poses() {
+        let build = |look_at, view_up| {
+            let pose = CameraPose::new(Point3::new(1.0, 2.0, 3.0), look_at, view_up, 90.0);
+            Camera::builder().pose(pose).build()
+        };
+        let up = Vector3::new(0.0, 1.0, 0.0);
+        assert!(matches!(
+            build(Point3::new(1.0, 2.0, 3.0), up),
+            Err(CameraPoseError::NoViewDirection)
+        ));
+        assert!(matches!(
+            build(Point3::new(1.0, -5.0, 3.0), up),
+            Err(CameraPoseError::ViewUpParallelToViewDirection)
+        ));
+        assert!(matches!(
+            build(Point3::origin(), Vector3::new(0.0, 0.0, 0.0)),
+            Err(CameraPoseError::ViewUpParallelToViewDirection)
+        ));
+        assert!(build(Point3::origin(), up).is_ok());
+    }
+
+    #[test]
+    fn reports_the_actual_aspect_ratio() {
+        let settings = RenderSettings::new(300, 200, 1, 1).ok().unwrap();
+        assert_eq!(settings.aspect_ratio(), 1.5);
+    }
 }
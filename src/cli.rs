@@ -0,0 +1,237 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub const HELP: &str = "\
+Render a scene description to an image.
+
+Usage: raytracer [OPTIONS] <SCENE>
+
+Arguments:
+  <SCENE>                  Path to the JSON scene description
+
+Options:
+  -o, --output <PATH>      Output image path [default: image.png]
+  -f, --format <FORMAT>    Output format: ppm, png, hdr, pfm or exr [default: from the output extension]
+  -W, --width <PIXELS>     Image width, overriding the scene
+  -H, --height <PIXELS>    Image height, overriding the scene
+  -s, --samples <COUNT>    Samples per pixel, overriding the scene
+  -d, --max-depth <COUNT>  Maximum number of ray bounces, overriding the scene
+  -t, --threads <COUNT>    Number of render threads [default: available cores]
+      --seed <SEED>        Seed for reproducible renders [default: random]
+  -h, --help               Print this help
+";
+
+pub enum Command {
+    Render(RenderArgs),
+    Help,
+}
+
+pub struct RenderArgs {
+    pub scene_path: PathBuf,
+    pub output_path: PathBuf,
+    pub format: Option<String>,
+    pub image_width: Option<u32>,
+    pub image_height: Option<u32>,
+    pub samples_per_pixel: Option<u32>,
+    pub max_depth: Option<u32>,
+    pub thread_count: Option<usize>,
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct CliError(String);
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, CliError> {
+    let mut scene_path = None;
+    let mut output_path = None;
+    let mut format = None;
+    let mut image_width = None;
+    let mut image_height = None;
+    let mut samples_per_pixel = None;
+    let mut max_depth = None;
+    let mut thread_count = None;
+    let mut seed = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        // Accept both `--name value` and `--name=value`.
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => {
+                (name.to_string(), Some(value.to_string()))
+            }
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| CliError(format!("option '{}' requires a value", name)))
+        };
+
+        match name.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-o" | "--output" => output_path = Some(PathBuf::from(value()?)),
+            "-f" | "--format" => format = Some(value()?),
+            "-W" | "--width" => image_width = Some(parse_count(&name, &value()?)?),
+            "-H" | "--height" => image_height = Some(parse_count(&name, &value()?)?),
+            "-s" | "--samples" => samples_per_pixel = Some(parse_count(&name, &value()?)?),
+            "-d" | "--max-depth" => max_depth = Some(parse_count(&name, &value()?)?),
+            "-t" | "--threads" => thread_count = Some(parse_count(&name, &value()?)?),
+            "--seed" => seed = Some(parse_value(&name, &value()?)?),
+            _ if name.starts_with('-') && name.len() > 1 => {
+                return Err(CliError(format!("unknown option '{}'", name)))
+            }
+            _ if scene_path.is_none() => scene_path = Some(PathBuf::from(arg)),
+            _ => return Err(CliError(format!("unexpected argument '{}'", arg))),
+        }
+    }
+
+    let scene_path = scene_path.ok_or_else(|| CliError("missing scene file".to_string()))?;
+
+    Ok(Command::Render(RenderArgs {
+        scene_path,
+        output_path: output_path.unwrap_or_else(|| PathBuf::from("image.png")),
+        format,
+        image_width,
+        image_height,
+        samples_per_pixel,
+        max_depth,
+        thread_count,
+        seed,
+    }))
+}
+
+fn parse_value<T: FromStr>(name: &str, value: &str) -> Result<T, CliError> {
+    value
+        .parse()
+        .map_err(|_| CliError(format!("invalid value '{}' for '{}'", value, name)))
+}
+
+// Parses a count that must be at least one and fit the target type.
+fn parse_count<T: FromStr + TryFrom<u64>>(name: &str, value: &str) -> Result<T, CliError> {
+    let count: u64 = parse_value(name, value)?;
+    if count == 0 {
+        return Err(CliError(format!("'{}' must be at least 1", name)));
+    }
+    T::try_from(count)
+        .map_err(|_| CliError(format!("value '{}' for '{}' is too large", value, name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, CliError> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn render_args(args: &[&str]) -> RenderArgs {
+        match parse(args) {
+            Ok(Command::Render(args)) => args,
+            Ok(Command::Help) => panic!("expected render arguments, got help"),
+            Err(error) => panic!("unexpected error: {error}"),
+        }
+    }
+
+    fn error(args: &[&str]) -> String {
+        match parse(args) {
+            Err(error) => error.to_string(),
+            Ok(_) => panic!("{args:?} should have been rejected"),
+        }
+    }
+
+    #[test]
+    fn uses_defaults_for_a_bare_scene_path() {
+        let args = render_args(&["scene.json"]);
+        assert_eq!(args.scene_path, PathBuf::from("scene.json"));
+        assert_eq!(args.output_path, PathBuf::from("image.png"));
+        assert_eq!(args.format, None);
+        assert_eq!(args.image_width, None);
+        assert_eq!(args.thread_count, None);
+        assert_eq!(args.seed, None);
+    }
+
+    #[test]
+    fn parses_short_long_and_inline_options() {
+        let args = render_args(&[
+            "-o",
+            "out.exr",
+            "--format=exr",
+            "-W",
+            "640",
+            "--height",
+            "480",
+            "scene.json",
+            "--samples=16",
+            "-d",
+            "8",
+            "-t",
+            "4",
+            "--seed",
+            "18446744073709551615",
+        ]);
+        assert_eq!(args.scene_path, PathBuf::from("scene.json"));
+        assert_eq!(args.output_path, PathBuf::from("out.exr"));
+        assert_eq!(args.format.as_deref(), Some("exr"));
+        assert_eq!(args.image_width, Some(640));
+        assert_eq!(args.image_height, Some(480));
+        assert_eq!(args.samples_per_pixel, Some(16));
+        assert_eq!(args.max_depth, Some(8));
+        assert_eq!(args.thread_count, Some(4));
+        assert_eq!(args.seed, Some(u64::MAX));
+    }
+
+    #[test]
+    fn help_takes_precedence() {
+        assert!(matches!(
+            parse(&["scene.json", "--help"]),
+            Ok(Command::Help)
+        ));
+        assert!(matches!(parse(&["-h"]), Ok(Command::Help)));
+    }
+
+    #[test]
+    fn rejects_zero_counts() {
+        for option in ["-W", "--height", "-s", "--max-depth", "-t"] {
+            assert_eq!(
+                error(&["scene.json", option, "0"]),
+                format!("'{}' must be at least 1", option)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert_eq!(
+            error(&["scene.json", "-W", "-5"]),
+            "invalid value '-5' for '-W'"
+        );
+        assert_eq!(
+            error(&["scene.json", "--samples=many"]),
+            "invalid value 'many' for '--samples'"
+        );
+        assert_eq!(
+            error(&["scene.json", "--seed", "1.5"]),
+            "invalid value '1.5' for '--seed'"
+        );
+        assert_eq!(
+            error(&["scene.json", "-W", "4294967296"]),
+            "value '4294967296' for '-W' is too large"
+        );
+    }
+
+    #[test]
+    fn rejects_missing_values_and_unknown_arguments() {
+        assert_eq!(error(&["scene.json", "-o"]), "option '-o' requires a value");
+        assert_eq!(error(&["scene.json", "--fast"]), "unknown option '--fast'");
+        assert_eq!(error(&["a.json", "b.json"]), "unexpected argument 'b.json'");
+        assert_eq!(error(&["-W", "10"]), "missing scene file");
+    }
+}
@@ -0,0 +1,134 @@
+use crate::float::Float;
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign};
+
+// Linear RGB radiance or reflectance. Colours add and filter each other component by component,
+// but never mix with positions or directions.
+#[derive(Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct Color {
+    pub r: Float,
+    pub g: Float,
+    pub b: Float,
+}
+
+impl Color {
+    pub fn new(r: Float, g: Float, b: Float) -> Self {
+        Self { r, g, b }
+    }
+
+    pub fn black() -> Self {
+        Self::new(0.0, 0.0, 0.0)
+    }
+
+    pub fn white() -> Self {
+        Self::new(1.0, 1.0, 1.0)
+    }
+
+    pub fn gray(value: Float) -> Self {
+        Self::new(value, value, value)
+    }
+
+    // Rec. 709 luminance.
+    pub fn luminance(&self) -> Float {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+
+    pub fn is_black(&self) -> bool {
+        self.r <= 0.0 && self.g <= 0.0 && self.b <= 0.0
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "({}, {}, {})", self.r, self.g, self.b)
+    }
+}
+
+impl Debug for Color {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "({}, {}, {})", self.r, self.g, self.b)
+    }
+}
+
+impl Default for Color {
+    fn default() -> Color {
+        Color::black()
+    }
+}
+
+impl Sum for Color {
+    fn sum<I: Iterator<Item = Color>>(iter: I) -> Color {
+        iter.fold(Color::black(), |total, color| total + color)
+    }
+}
+
+// Arithmetic operators
+impl Add for Color {
+    type Output = Color;
+    fn add(self, other: Color) -> Color {
+        Color::new(self.r + other.r, self.g + other.g, self.b + other.b)
+    }
+}
+
+impl Mul for Color {
+    type Output = Color;
+    fn mul(self, other: Color) -> Color {
+        Color::new(self.r * other.r, self.g * other.g, self.b * other.b)
+    }
+}
+
+impl Mul<Float> for Color {
+    type Output = Color;
+    fn mul(self, other: Float) -> Color {
+        Color::new(self.r * other, self.g * other, self.b * other)
+    }
+}
+
+impl Mul<Color> for Float {
+    type Output = Color;
+    fn mul(self, other: Color) -> Color {
+        Color::new(self * other.r, self * other.g, self * other.b)
+    }
+}
+
+impl Div<Float> for Color {
+    type Output = Color;
+    fn div(self, other: Float) -> Color {
+        Color::new(self.r / other, self.g / other, self.b / other)
+    }
+}
+
+// Assignment operators
+impl AddAssign for Color {
+    fn add_assign(&mut self, other: Color) {
+        self.r += other.r;
+        self.g += other.g;
+        self.b += other.b;
+    }
+}
+
+impl MulAssign for Color {
+    fn mul_assign(&mut self, other: Color) {
+        self.r *= other.r;
+        self.g *= other.g;
+        self.b *= other.b;
+    }
+}
+
+impl MulAssign<Float> for Color {
+    fn mul_assign(&mut self, other: Float) {
+        self.r *= other;
+        self.g *= other;
+        self.b *= other;
+    }
+}
+
+impl DivAssign<Float> for Color {
+    fn div_assign(&mut self, other: Float) {
+        self.r /= other;
+        self.g /= other;
+        self.b /= other;
+    }
+}
@@ -0,0 +1,221 @@
+// Piecewise-constant distributions used to importance sample tabulated functions such as
+// environment maps.
+
+use crate::float::Float;
+
+pub struct Distribution1D {
+    function: Vec<Float>,
+    cdf: Vec<Float>,
+    integral: Float,
+}
+
+impl Distribution1D {
+    // Returns `None` for an empty function, which has nothing to sample.
+    pub fn new(function: Vec<Float>) -> Option<Self> {
+        if function.is_empty() {
+            return None;
+        }
+        let count = function.len() as Float;
+        let mut cdf = Vec::with_capacity(function.len() + 1);
+        cdf.push(0.0);
+        for value in &function {
+            let previous = cdf[cdf.len() - 1];
+            cdf.push(previous + value.abs() / count);
+        }
+
+        let integral = cdf[cdf.len() - 1];
+        if integral > 0.0 {
+            for value in cdf.iter_mut() {
+                *value /= integral;
+            }
+        } else {
+            for (i, value) in cdf.iter_mut().enumerate() {
+                *value = i as Float / count;
+            }
+        }
+
+        Some(Self {
+            function,
+            cdf,
+            integral,
+        })
+    }
+
+    pub fn count(&self) -> usize {
+        self.function.len()
+    }
+
+    pub fn integral(&self) -> Float {
+        self.integral
+    }
+
+    // Maps `u` in [0, 1) to a sample in [0, 1), returning it with its density and the index of
+    // the segment it fell into.
+    pub fn sample(&self, u: Float) -> (Float, Float, usize) {
+        let offset = self
+            .cdf
+            .partition_point(|&value| value <= u)
+            .saturating_sub(1)
+            .min(self.count() - 1);
+
+        let mut du = u - self.cdf[offset];
+        let width = self.cdf[offset + 1] - self.cdf[offset];
+        if width > 0.0 {
+            du /= width;
+        }
+
+        let x = (offset as Float + du) / self.count() as Float;
+        (x, self.pdf_at(offset), offset)
+    }
+
+    pub fn pdf(&self, x: Float) -> Float {
+        self.pdf_at(self.index(x))
+    }
+
+    fn index(&self, x: Float) -> usize {
+        ((x * self.count() as Float).max(0.0) as usize).min(self.count() - 1)
+    }
+
+    fn pdf_at(&self, offset: usize) -> Float {
+        if self.integral > 0.0 {
+            self.function[offset].abs() / self.integral
+        } else {
+            1.0
+        }
+    }
+}
+
+pub struct Distribution2D {
+    conditionals: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    // Builds a distribution over a `width` x `height` grid of values stored row by row. Returns
+    // `None` when the grid is empty or `function` does not hold exactly that many values.
+    pub fn new(function: &[Float], width: usize, height: usize) -> Option<Self> {
+        if width == 0 || width.checked_mul(height) != Some(function.len()) {
+            return None;
+        }
+        let conditionals = function
+            .chunks_exact(width)
+            .map(|row| Distribution1D::new(row.to_vec()))
+            .collect::<Option<Vec<_>>>()?;
+        let marginal = Distribution1D::new(
+            conditionals
+                .iter()
+                .map(|conditional| conditional.integral())
+                .collect(),
+        )?;
+
+        Some(Self {
+            conditionals,
+            marginal,
+        })
+    }
+
+    pub fn sample(&self, u0: Float, u1: Float) -> ((Float, Float), Float) {
+        let (y, pdf_y, row) = self.marginal.sample(u1);
+        let (x, pdf_x, _) = self.conditionals[row].sample(u0);
+        ((x, y), pdf_x * pdf_y)
+    }
+
+    pub fn pdf(&self, x: Float, y: Float) -> Float {
+        let row = self.marginal.index(y);
+        self.conditionals[row].pdf(x) * self.marginal.pdf(y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: Float, expected: Float) {
+        assert!(
+            (actual - expected).abs() < 1e-5,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn samples_1d_in_proportion_to_the_function() {
+        let distribution = Distribution1D::new(vec![1.0, 3.0]).unwrap();
+        assert_close(distribution.integral(), 2.0);
+
+        let (x, pdf, offset) = distribution.sample(0.1);
+        assert_close(x, 0.2);
+        assert_close(pdf, 0.5);
+        assert_eq!(offset, 0);
+
+        let (x, pdf, offset) = distribution.sample(0.625);
+        assert_close(x, 0.75);
+        assert_close(pdf, 1.5);
+        assert_eq!(offset, 1);
+    }
+
+    #[test]
+    fn pdf_1d_matches_sampled_density() {
+        let distribution = Distribution1D::new(vec![0.5, 2.0, 0.0, 1.5]).unwrap();
+        for i in 0..100 {
+            let (x, pdf, _) = distribution.sample(i as Float / 100.0);
+            assert_close(distribution.pdf(x), pdf);
+        }
+    }
+
+    #[test]
+    fn never_samples_zero_valued_segments() {
+        let distribution = Distribution1D::new(vec![0.0, 1.0, 0.0]).unwrap();
+        for u in [0.0, 0.3, 0.5, 0.999] {
+            let (x, pdf, offset) = distribution.sample(u);
+            assert_eq!(offset, 1);
+            assert!((1.0 / 3.0..2.0 / 3.0).contains(&x));
+            assert_close(pdf, 3.0);
+        }
+    }
+
+    #[test]
+    fn falls_back_to_uniform_for_an_all_zero_function() {
+        let distribution = Distribution1D::new(vec![0.0; 4]).unwrap();
+        let (x, pdf, offset) = distribution.sample(0.6);
+        assert_close(x, 0.6);
+        assert_close(pdf, 1.0);
+        assert_eq!(offset, 2);
+        assert_close(distribution.pdf(0.1), 1.0);
+    }
+
+    #[test]
+    fn samples_2d_rows_then_columns() {
+        let distribution = Distribution2D::new(&[1.0, 1.0, 2.0, 4.0], 2, 2).unwrap();
+
+        let ((x, y), pdf) = distribution.sample(0.5, 0.5);
+        assert_close(x, 0.625);
+        assert_close(y, 2.0 / 3.0);
+        // The density is the function value over its mean.
+        assert_close(pdf, 2.0);
+        assert_close(distribution.pdf(x, y), pdf);
+    }
+
+    #[test]
+    fn pdf_2d_integrates_to_one() {
+        let function = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0];
+        let distribution = Distribution2D::new(&function, 4, 3).unwrap();
+
+        let mut total = 0.0;
+        for y in 0..3 {
+            for x in 0..4 {
+                let center = ((x as Float + 0.5) / 4.0, (y as Float + 0.5) / 3.0);
+                total += distribution.pdf(center.0, center.1) / 12.0;
+            }
+        }
+        assert_close(total, 1.0);
+    }
+
+    #[test]
+    fn rejects_empty_and_mismatched_functions() {
+        assert!(Distribution1D::new(vec![]).is_none());
+        assert!(Distribution2D::new(&[], 0, 0).is_none());
+        assert!(Distribution2D::new(&[], 0, 3).is_none());
+        assert!(Distribution2D::new(&[], 3, 0).is_none());
+        assert!(Distribution2D::new(&[1.0, 2.0, 3.0], 2, 2).is_none());
+    }
+}
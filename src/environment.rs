@@ -0,0 +1,196 @@
+use crate::color::Color;
+use crate::distribution::Distribution2D;
+use crate::float::consts::PI;
+use crate::float::Float;
+use crate::image::Image;
+use crate::input::{read_image, InputError};
+use crate::utility::{random_from_range, CLOSEST_TO_ZERO_TO_ONE_RANGE};
+use crate::vector::{unit_vector, Vector3};
+use std::path::Path;
+
+pub struct EnvironmentSample {
+    pub direction: Vector3,
+    pub radiance: Color,
+    pub pdf: Float,
+}
+
+pub trait Environment: Send + Sync {
+    fn color(&self, direction: Vector3) -> Color;
+
+    // Environments that can be importance sampled return a direction towards them together with
+    // its solid angle density, which the camera uses for next event estimation.
+    fn sample(&self) -> Option<EnvironmentSample> {
+        None
+    }
+
+    fn pdf(&self, _direction: Vector3) -> Float {
+        0.0
+    }
+}
+
+pub struct SolidColor {
+    color: Color,
+}
+
+impl SolidColor {
+    pub fn new(color: Color) -> Self {
+        Self { color }
+    }
+}
+
+impl Environment for SolidColor {
+    fn color(&self, _direction: Vector3) -> Color {
+        self.color
+    }
+}
+
+pub struct Gradient {
+    bottom: Color,
+    top: Color,
+}
+
+impl Gradient {
+    pub fn new(bottom: Color, top: Color) -> Self {
+        Self { bottom, top }
+    }
+}
+
+impl Default for Gradient {
+    fn default() -> Self {
+        Self::new(Color::white(), Color::new(0.5, 0.7, 1.0))
+    }
+}
+
+impl Environment for Gradient {
+    fn color(&self, direction: Vector3) -> Color {
+        let unit_direction = unit_vector(direction);
+        let a = 0.5 * (unit_direction.y + 1.0);
+        (1.0 - a) * self.bottom + a * self.top
+    }
+}
+
+pub struct ImageEnvironment {
+    image: Image,
+}
+
+impl ImageEnvironment {
+    // Returns `None` for an empty image.
+    pub fn new(image: Image) -> Option<Self> {
+        (image.width() > 0 && image.height() > 0).then_some(Self { image })
+    }
+}
+
+impl Environment for ImageEnvironment {
+    fn color(&self, direction: Vector3) -> Color {
+        let (u, v) = direction_to_equirectangular(direction);
+        equirectangular_pixel(&self.image, u, v)
+    }
+}
+
+pub struct EnvironmentLight {
+    image: Image,
+    distribution: Distribution2D,
+}
+
+impl EnvironmentLight {
+    // Returns `None` for an empty image.
+    pub fn new(image: Image) -> Option<Self> {
+        // Rows near the poles cover less solid angle, so their luminance is weighted by sin(theta).
+        let mut weights = Vec::with_capacity(image.width() * image.height());
+        for y in 0..image.height() {
+            let sin_theta = (PI * (y as Float + 0.5) / image.height() as Float).sin();
+            for x in 0..image.width() {
+                weights.push(image.pixel(x, y).luminance() * sin_theta);
+            }
+        }
+        let distribution = Distribution2D::new(&weights, image.width(), image.height())?;
+
+        Some(Self {
+            image,
+            distribution,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, InputError> {
+        Self::new(read_image(path)?)
+            .ok_or_else(|| InputError::Malformed("the image is empty".to_string()))
+    }
+}
+
+impl Environment for EnvironmentLight {
+    fn color(&self, direction: Vector3) -> Color {
+        let (u, v) = direction_to_equirectangular(direction);
+        equirectangular_pixel(&self.image, u, v)
+    }
+
+    fn sample(&self) -> Option<EnvironmentSample> {
+        let ((u, v), uv_pdf) = self.distribution.sample(
+            random_from_range(CLOSEST_TO_ZERO_TO_ONE_RANGE),
+            random_from_range(CLOSEST_TO_ZERO_TO_ONE_RANGE),
+        );
+        if uv_pdf <= 0.0 {
+            return None;
+        }
+
+        let sin_theta = (v * PI).sin();
+        if sin_theta <= 0.0 {
+            return None;
+        }
+
+        Some(EnvironmentSample {
+            direction: equirectangular_to_direction(u, v),
+            radiance: equirectangular_pixel(&self.image, u, v),
+            pdf: uv_pdf / (2.0 * PI * PI * sin_theta),
+        })
+    }
+
+    fn pdf(&self, direction: Vector3) -> Float {
+        let (u, v) = direction_to_equirectangular(direction);
+        let sin_theta = (v * PI).sin();
+        if sin_theta <= 0.0 {
+            0.0
+        } else {
+            self.distribution.pdf(u, v) / (2.0 * PI * PI * sin_theta)
+        }
+    }
+}
+
+// Maps a direction onto latitude-longitude image coordinates in [0, 1], with -Z at the centre
+// of the image and +Y along the top edge.
+pub fn direction_to_equirectangular(direction: Vector3) -> (Float, Float) {
+    let unit_direction = unit_vector(direction);
+    let u = 0.5 + unit_direction.x.atan2(-unit_direction.z) / (2.0 * PI);
+    let v = unit_direction.y.clamp(-1.0, 1.0).acos() / PI;
+    (u, v)
+}
+
+pub fn equirectangular_to_direction(u: Float, v: Float) -> Vector3 {
+    let phi = (u - 0.5) * 2.0 * PI;
+    let theta = v * PI;
+    Vector3::new(
+        theta.sin() * phi.sin(),
+        theta.cos(),
+        -theta.sin() * phi.cos(),
+    )
+}
+
+fn equirectangular_pixel(image: &Image, u: Float, v: Float) -> Color {
+    let x = ((u * image.width() as Float).max(0.0) as usize).min(image.width() - 1);
+    let y = ((v * image.height() as Float).max(0.0) as usize).min(image.height() - 1);
+    image.pixel(x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_empty_images() {
+        for (width, height) in [(0, 0), (0, 2), (2, 0)] {
+            assert!(ImageEnvironment::new(Image::new(width, height)).is_none());
+            assert!(EnvironmentLight::new(Image::new(width, height)).is_none());
+        }
+        assert!(ImageEnvironment::new(Image::new(2, 1)).is_some());
+        assert!(EnvironmentLight::new(Image::new(2, 1)).is_some());
+    }
+}
@@ -0,0 +1,22 @@
+// The scalar type used for geometry and shading. The `f32` feature switches the renderer to
+// single precision, trading accuracy for throughput.
+#[cfg(not(feature = "f32"))]
+pub type Float = f64;
+#[cfg(feature = "f32")]
+pub type Float = f32;
+
+#[cfg(feature = "f32")]
+pub use std::f32::consts;
+#[cfg(not(feature = "f32"))]
+pub use std::f64::consts;
+
+// Rounding error in a computed position grows with its distance from the origin. Tolerances
+// against it are this many units in the last place of the coordinates involved, so they widen
+// both for larger scenes and for the coarser single precision.
+pub const ROUNDING_TOLERANCE: Float = 256.0 * Float::EPSILON;
+
+// Narrows a value for single precision file formats. This is a no-op with the `f32` feature.
+#[allow(clippy::unnecessary_cast)]
+pub fn to_f32(value: Float) -> f32 {
+    value as f32
+}
@@ -1,29 +1,40 @@
+use crate::aabb::{Aabb, EMPTY_BOX};
+use crate::color::Color;
+use crate::float::Float;
 use crate::interval::Interval;
 use crate::material::Material;
+use crate::normal::Normal3;
+use crate::point::Point3;
 use crate::ray::Ray;
-use crate::vector::{dot, Vector3};
+use crate::vector::Vector3;
 
-pub trait Hittable {
-    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord>;
+pub trait Hittable: Send + Sync {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>>;
+
+    fn bounding_box(&self) -> Aabb;
 }
 
 pub struct HitRecord<'obj> {
-    pub point: Vector3,
-    pub normal: Vector3,
+    pub point: Point3,
+    pub normal: Normal3,
     pub material: &'obj dyn Material,
-    pub t: f64,
+    pub t: Float,
+    pub u: Float,
+    pub v: Float,
+    pub vertex_color: Option<Color>,
     pub is_front_face: bool,
 }
 
 impl<'obj> HitRecord<'obj> {
     pub fn new(
-        point: Vector3,
-        t: f64,
-        outward_normal: Vector3,
-        ray_direction: &Vector3,
+        point: Point3,
+        t: Float,
+        (u, v): (Float, Float),
+        outward_normal: Normal3,
+        ray_direction: Vector3,
         material: &'obj dyn Material,
     ) -> Self {
-        let is_front_face = dot(ray_direction, &outward_normal) < 0.0;
+        let is_front_face = outward_normal.dot(ray_direction) < 0.0;
         let normal = if is_front_face {
             outward_normal
         } else {
@@ -34,31 +45,50 @@ impl<'obj> HitRecord<'obj> {
             normal,
             material,
             t,
+            u,
+            v,
+            vertex_color: None,
             is_front_face,
         }
     }
 }
 
 pub struct HittableList {
-    pub objects: Vec<Box<dyn Hittable>>,
+    objects: Vec<Box<dyn Hittable>>,
+    bbox: Aabb,
 }
 
 impl HittableList {
     pub fn new() -> Self {
-        Self { objects: vec![] }
+        Self {
+            objects: vec![],
+            bbox: EMPTY_BOX,
+        }
     }
 
     pub fn add(&mut self, object: Box<dyn Hittable>) {
+        self.bbox = Aabb::surrounding(&self.bbox, &object.bounding_box());
         self.objects.push(object);
     }
 
     pub fn clear(&mut self) {
         self.objects.clear();
+        self.bbox = EMPTY_BOX;
+    }
+
+    pub(crate) fn into_objects(self) -> Vec<Box<dyn Hittable>> {
+        self.objects
+    }
+}
+
+impl Default for HittableList {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Hittable for HittableList {
-    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
         let mut closest_hit_record: Option<HitRecord> = None;
         let mut closest_so_far = ray_t.max;
 
@@ -71,4 +101,8 @@ impl Hittable for HittableList {
 
         closest_hit_record
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox.clone()
+    }
 }
@@ -0,0 +1,100 @@
+use crate::image::Image;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs;
+use std::io::Error as IoError;
+use std::path::Path;
+
+mod hdr;
+mod pfm;
+
+#[derive(Debug)]
+pub enum InputError {
+    UnsupportedFormat(String),
+    Malformed(String),
+    Io(IoError),
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            InputError::UnsupportedFormat(extension) => {
+                write!(f, "unsupported input format: '{}'", extension)
+            }
+            InputError::Malformed(reason) => write!(f, "malformed image: {}", reason),
+            InputError::Io(error) => write!(f, "could not read image: {}", error),
+        }
+    }
+}
+
+impl From<IoError> for InputError {
+    fn from(error: IoError) -> Self {
+        InputError::Io(error)
+    }
+}
+
+pub fn read_image(path: &Path) -> Result<Image, InputError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    match extension.as_str() {
+        "hdr" => hdr::decode(&fs::read(path)?),
+        "pfm" => pfm::decode(&fs::read(path)?),
+        _ => Err(InputError::UnsupportedFormat(extension)),
+    }
+}
+
+// Reads a newline-terminated header line starting at `position`, advancing past it.
+fn read_line<'a>(bytes: &'a [u8], position: &mut usize) -> Result<&'a str, InputError> {
+    let start = *position;
+    let length = bytes[start..]
+        .iter()
+        .position(|&byte| byte == b'\n')
+        .ok_or_else(|| InputError::Malformed("unexpected end of header".to_string()))?;
+    *position = start + length + 1;
+
+    std::str::from_utf8(&bytes[start..start + length])
+        .map(str::trim)
+        .map_err(|_| InputError::Malformed("header is not valid text".to_string()))
+}
+
+fn parse_dimension(value: &str) -> Result<usize, InputError> {
+    match value.parse() {
+        Ok(0) | Err(_) => Err(InputError::Malformed(format!(
+            "invalid image dimension '{}'",
+            value
+        ))),
+        Ok(dimension) => Ok(dimension),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_positive_dimensions() {
+        assert_eq!(parse_dimension("640").unwrap(), 640);
+    }
+
+    #[test]
+    fn rejects_zero_and_invalid_dimensions() {
+        for value in ["0", "-1", "", "12px"] {
+            assert!(matches!(
+                parse_dimension(value),
+                Err(InputError::Malformed(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn read_line_advances_past_the_newline() {
+        let bytes = b"PF \n2 1\n";
+        let mut position = 0;
+        assert_eq!(read_line(bytes, &mut position).unwrap(), "PF");
+        assert_eq!(read_line(bytes, &mut position).unwrap(), "2 1");
+        assert!(read_line(bytes, &mut position).is_err());
+    }
+}
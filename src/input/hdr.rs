@@ -0,0 +1,241 @@
+use crate::color::Color;
+use crate::float::Float;
+use crate::image::Image;
+use crate::input::{parse_dimension, read_line, InputError};
+
+pub fn decode(bytes: &[u8]) -> Result<Image, InputError> {
+    let mut position = 0;
+
+    let signature = read_line(bytes, &mut position)?;
+    if !signature.starts_with("#?") {
+        return Err(InputError::Malformed(
+            "missing Radiance signature".to_string(),
+        ));
+    }
+
+    loop {
+        let line = read_line(bytes, &mut position)?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some(format) = line.strip_prefix("FORMAT=") {
+            if format != "32-bit_rle_rgbe" {
+                return Err(InputError::Malformed(format!(
+                    "unsupported format '{}'",
+                    format
+                )));
+            }
+        }
+    }
+
+    let resolution = read_line(bytes, &mut position)?;
+    let (width, height) = match resolution.split_whitespace().collect::<Vec<_>>()[..] {
+        ["-Y", height, "+X", width] => (parse_dimension(width)?, parse_dimension(height)?),
+        _ => {
+            return Err(InputError::Malformed(format!(
+                "unsupported resolution line '{}'",
+                resolution
+            )))
+        }
+    };
+
+    // Check that the data can hold every scanline before allocating the image, so a corrupt
+    // resolution line cannot request an arbitrary amount of memory.
+    if width.checked_mul(height).is_none() {
+        return Err(InputError::Malformed(format!(
+            "image of {}x{} is too large",
+            width, height
+        )));
+    }
+    match min_scanline_size(width).and_then(|size| size.checked_mul(height)) {
+        Some(size) if size <= bytes.len() - position => {}
+        _ => {
+            return Err(InputError::Malformed(
+                "unexpected end of pixel data".to_string(),
+            ))
+        }
+    }
+
+    let mut image = Image::new(width, height);
+    let mut scanline = vec![[0_u8; 4]; width];
+    for y in 0..height {
+        position = read_scanline(bytes, position, &mut scanline)?;
+        for (x, rgbe) in scanline.iter().enumerate() {
+            image.set_pixel(x, y, rgbe_to_color(rgbe));
+        }
+    }
+
+    Ok(image)
+}
+
+// The fewest bytes a scanline of `width` pixels can take. A run-length encoded one has a header
+// and at least one two-byte run per 127 pixels of each channel, a flat one four bytes per pixel.
+fn min_scanline_size(width: usize) -> Option<usize> {
+    let flat = width.checked_mul(4)?;
+    if is_run_length_width(width) {
+        Some(flat.min(4 + 8 * width.div_ceil(127)))
+    } else {
+        Some(flat)
+    }
+}
+
+fn is_run_length_width(width: usize) -> bool {
+    (8..0x8000).contains(&width)
+}
+
+fn read_scanline(
+    bytes: &[u8],
+    mut position: usize,
+    scanline: &mut [[u8; 4]],
+) -> Result<usize, InputError> {
+    let width = scanline.len();
+    let truncated = || InputError::Malformed("unexpected end of pixel data".to_string());
+
+    let header = bytes.get(position..position + 4).ok_or_else(truncated)?;
+    let is_run_length_encoded = is_run_length_width(width)
+        && header[0] == 2
+        && header[1] == 2
+        && ((header[2] as usize) << 8 | header[3] as usize) == width;
+
+    if !is_run_length_encoded {
+        for pixel in scanline.iter_mut() {
+            let rgbe = bytes.get(position..position + 4).ok_or_else(truncated)?;
+            pixel.copy_from_slice(rgbe);
+            position += 4;
+        }
+        return Ok(position);
+    }
+
+    position += 4;
+    // Each channel of the scanline is stored separately as a sequence of runs and literals.
+    for channel in 0..4 {
+        let mut x = 0;
+        while x < width {
+            let count = *bytes.get(position).ok_or_else(truncated)? as usize;
+            position += 1;
+
+            let is_run = count > 128;
+            let length = if is_run { count - 128 } else { count };
+            if length == 0 || x + length > width {
+                return Err(InputError::Malformed("invalid run length".to_string()));
+            }
+
+            if is_run {
+                let value = *bytes.get(position).ok_or_else(truncated)?;
+                position += 1;
+                for pixel in &mut scanline[x..x + length] {
+                    pixel[channel] = value;
+                }
+            } else {
+                let values = bytes
+                    .get(position..position + length)
+                    .ok_or_else(truncated)?;
+                position += length;
+                for (pixel, &value) in scanline[x..x + length].iter_mut().zip(values) {
+                    pixel[channel] = value;
+                }
+            }
+            x += length;
+        }
+    }
+
+    Ok(position)
+}
+
+fn rgbe_to_color(rgbe: &[u8; 4]) -> Color {
+    if rgbe[3] == 0 {
+        return Color::black();
+    }
+
+    let scale = Float::powi(2.0, rgbe[3] as i32 - (128 + 8));
+    Color::new(
+        (rgbe[0] as Float + 0.5) * scale,
+        (rgbe[1] as Float + 0.5) * scale,
+        (rgbe[2] as Float + 0.5) * scale,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::{HdrWriter, ImageWriter};
+
+    #[test]
+    fn decodes_flat_scanlines_written_by_the_writer() {
+        let mut image = Image::new(2, 2);
+        image.set_pixel(0, 0, Color::new(1.0, 0.5, 0.25));
+        image.set_pixel(1, 1, Color::new(4.0, 4.0, 4.0));
+
+        let mut bytes = vec![];
+        HdrWriter.write(&image, &mut bytes).unwrap();
+        let decoded = decode(&bytes).unwrap();
+
+        assert_eq!((decoded.width(), decoded.height()), (2, 2));
+        assert_eq!(decoded.pixel(1, 0), Color::black());
+        for (x, y) in [(0, 0), (1, 1)] {
+            let (original, decoded) = (image.pixel(x, y), decoded.pixel(x, y));
+            assert!((original.r - decoded.r).abs() < 0.02 * original.r);
+            assert!((original.g - decoded.g).abs() < 0.02 * original.r);
+            assert!((original.b - decoded.b).abs() < 0.02 * original.r);
+        }
+    }
+
+    #[test]
+    fn decodes_run_length_encoded_scanlines() {
+        let mut bytes = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X 8\n".to_vec();
+        bytes.extend_from_slice(&[2, 2, 0, 8]);
+        // Red is a run, green a literal span, blue and exponent are runs.
+        bytes.extend_from_slice(&[128 + 8, 128]);
+        bytes.extend_from_slice(&[8, 0, 16, 32, 48, 64, 80, 96, 112]);
+        bytes.extend_from_slice(&[128 + 8, 0]);
+        bytes.extend_from_slice(&[128 + 8, 129]);
+
+        let image = decode(&bytes).unwrap();
+        assert_eq!(image.width(), 8);
+        for x in 0..8 {
+            let color = image.pixel(x, 0);
+            assert!((color.r - 128.5 / 128.0).abs() < 1e-6);
+            assert!((color.g - (16 * x) as Float / 128.0 - 0.5 / 128.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn rejects_zero_dimensions() {
+        for resolution in ["-Y 0 +X 4", "-Y 4 +X 0"] {
+            let bytes = format!("#?RADIANCE\n\n{}\n", resolution);
+            assert!(matches!(
+                decode(bytes.as_bytes()),
+                Err(InputError::Malformed(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_pixel_data() {
+        let bytes = b"#?RADIANCE\n\n-Y 2 +X 2\n\x80\x80\x80\x81";
+        assert!(matches!(decode(bytes), Err(InputError::Malformed(_))));
+    }
+
+    #[test]
+    fn rejects_dimensions_larger_than_the_data_before_allocating() {
+        for resolution in [
+            "-Y 100000000 +X 100000000",
+            "-Y 18446744073709551615 +X 18446744073709551615",
+            "-Y 1 +X 18446744073709551615",
+        ] {
+            let bytes = format!("#?RADIANCE\n\n{}\n\x02\x02\x00\x08", resolution);
+            assert!(matches!(
+                decode(bytes.as_bytes()),
+                Err(InputError::Malformed(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn bounds_scanline_sizes_by_the_smallest_encoding() {
+        assert_eq!(min_scanline_size(4), Some(16));
+        assert_eq!(min_scanline_size(8), Some(12));
+        assert_eq!(min_scanline_size(128), Some(20));
+        assert_eq!(min_scanline_size(usize::MAX), None);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::color::Color;
+use crate::float::Float;
+use crate::image::Image;
+use crate::input::{parse_dimension, read_line, InputError};
+
+pub fn decode(bytes: &[u8]) -> Result<Image, InputError> {
+    let mut position = 0;
+
+    let channels = match read_line(bytes, &mut position)? {
+        "PF" => 3,
+        "Pf" => 1,
+        signature => {
+            return Err(InputError::Malformed(format!(
+                "unknown PFM signature '{}'",
+                signature
+            )))
+        }
+    };
+
+    let dimensions = read_line(bytes, &mut position)?;
+    let (width, height) = match dimensions.split_whitespace().collect::<Vec<_>>()[..] {
+        [width, height] => (parse_dimension(width)?, parse_dimension(height)?),
+        _ => {
+            return Err(InputError::Malformed(format!(
+                "invalid PFM dimensions '{}'",
+                dimensions
+            )))
+        }
+    };
+
+    let scale = read_line(bytes, &mut position)?;
+    let is_little_endian = scale
+        .parse::<Float>()
+        .map_err(|_| InputError::Malformed(format!("invalid PFM scale '{}'", scale)))?
+        < 0.0;
+
+    // The size is checked against the data before anything is allocated.
+    let data_size = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(channels * 4))
+        .ok_or_else(|| {
+            InputError::Malformed(format!("image of {}x{} is too large", width, height))
+        })?;
+    let data = bytes
+        .get(position..)
+        .and_then(|data| data.get(..data_size))
+        .ok_or_else(|| InputError::Malformed("unexpected end of pixel data".to_string()))?;
+    let samples: Vec<Float> = data
+        .chunks_exact(4)
+        .map(|sample| {
+            let sample = [sample[0], sample[1], sample[2], sample[3]];
+            if is_little_endian {
+                f32::from_le_bytes(sample) as Float
+            } else {
+                f32::from_be_bytes(sample) as Float
+            }
+        })
+        .collect();
+
+    // Scanlines are stored bottom-to-top.
+    let mut image = Image::new(width, height);
+    for (row, pixels) in samples.chunks_exact((width * channels).max(1)).enumerate() {
+        for (x, pixel) in pixels.chunks_exact(channels).enumerate() {
+            let color = if channels == 3 {
+                Color::new(pixel[0], pixel[1], pixel[2])
+            } else {
+                Color::new(pixel[0], pixel[0], pixel[0])
+            };
+            image.set_pixel(x, height - 1 - row, color);
+        }
+    }
+
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::{ImageWriter, PfmWriter};
+
+    #[test]
+    fn round_trips_images_written_by_the_writer() {
+        let mut image = Image::new(2, 3);
+        image.set_pixel(0, 0, Color::new(1.0, 2.0, 3.0));
+        image.set_pixel(1, 2, Color::new(0.25, 0.5, 100.0));
+
+        let mut bytes = vec![];
+        PfmWriter.write(&image, &mut bytes).unwrap();
+        let decoded = decode(&bytes).unwrap();
+
+        assert_eq!(decoded.pixels(), image.pixels());
+    }
+
+    #[test]
+    fn decodes_big_endian_grayscale() {
+        let mut bytes = b"Pf\n1 2\n1.0\n".to_vec();
+        bytes.extend_from_slice(&0.5_f32.to_be_bytes());
+        bytes.extend_from_slice(&2.0_f32.to_be_bytes());
+
+        let image = decode(&bytes).unwrap();
+        assert_eq!(image.pixel(0, 0), Color::new(2.0, 2.0, 2.0));
+        assert_eq!(image.pixel(0, 1), Color::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn rejects_zero_dimensions() {
+        for dimensions in ["0 4", "4 0", "0 0"] {
+            let bytes = format!("PF\n{}\n-1.0\n", dimensions);
+            assert!(matches!(
+                decode(bytes.as_bytes()),
+                Err(InputError::Malformed(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_pixel_data() {
+        let mut bytes = b"PF\n1 1\n-1.0\n".to_vec();
+        bytes.extend_from_slice(&1.0_f32.to_le_bytes());
+        assert!(matches!(decode(&bytes), Err(InputError::Malformed(_))));
+    }
+
+    #[test]
+    fn rejects_dimensions_larger_than_the_data_before_allocating() {
+        for dimensions in ["100000000 100000000", "18446744073709551615 2"] {
+            let bytes = format!("PF\n{}\n-1.0\n", dimensions);
+            assert!(matches!(
+                decode(bytes.as_bytes()),
+                Err(InputError::Malformed(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,402 @@
+// A minimal JSON (RFC 8259) parser that remembers where every value starts, so that callers can
+// report errors against the line and column of the offending input.
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+// Arrays and objects are parsed recursively, so their nesting is limited to keep malicious input
+// from overflowing the stack.
+const MAX_NESTING_DEPTH: usize = 128;
+
+pub struct Json {
+    pub value: JsonValue,
+    pub line: usize,
+    pub column: usize,
+}
+
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn type_name(&self) -> &'static str {
+        match self.value {
+            JsonValue::Null => "null",
+            JsonValue::Bool(_) => "a boolean",
+            JsonValue::Number(_) => "a number",
+            JsonValue::String(_) => "a string",
+            JsonValue::Array(_) => "an array",
+            JsonValue::Object(_) => "an object",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct JsonError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+pub fn parse(source: &str) -> Result<Json, JsonError> {
+    let mut parser = Parser {
+        chars: source.chars().collect(),
+        position: 0,
+        line: 1,
+        column: 1,
+        depth: 0,
+    };
+
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.peek().is_some() {
+        return Err(parser.error("unexpected trailing characters"));
+    }
+    Ok(value)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    line: usize,
+    column: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, message: &str) -> JsonError {
+        JsonError {
+            line: self.line,
+            column: self.column,
+            message: message.to_string(),
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.next();
+                Ok(())
+            }
+            Some(c) => Err(self.error(&format!("expected '{}' but found '{}'", expected, c))),
+            None => Err(self.error(&format!("expected '{}' but reached end of input", expected))),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.next();
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Json, JsonError> {
+        self.skip_whitespace();
+        let line = self.line;
+        let column = self.column;
+
+        let value = match self.peek() {
+            Some(c @ ('{' | '[')) => {
+                if self.depth == MAX_NESTING_DEPTH {
+                    return Err(self.error("arrays and objects are nested too deeply"));
+                }
+                self.depth += 1;
+                let value = if c == '{' {
+                    self.parse_object()
+                } else {
+                    self.parse_array()
+                };
+                self.depth -= 1;
+                value?
+            }
+            Some('"') => JsonValue::String(self.parse_string()?),
+            Some('-' | '0'..='9') => JsonValue::Number(self.parse_number()?),
+            Some('t') => self.parse_literal("true", JsonValue::Bool(true))?,
+            Some('f') => self.parse_literal("false", JsonValue::Bool(false))?,
+            Some('n') => self.parse_literal("null", JsonValue::Null)?,
+            Some(c) => return Err(self.error(&format!("unexpected character '{}'", c))),
+            None => return Err(self.error("unexpected end of input")),
+        };
+
+        Ok(Json {
+            value,
+            line,
+            column,
+        })
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: JsonValue) -> Result<JsonValue, JsonError> {
+        for expected in literal.chars() {
+            if self.peek() != Some(expected) {
+                return Err(self.error(&format!("invalid literal, expected '{}'", literal)));
+            }
+            self.next();
+        }
+        Ok(value)
+    }
+
+    fn parse_object(&mut self) -> Result<JsonValue, JsonError> {
+        self.expect('{')?;
+        let mut members: Vec<(String, Json)> = vec![];
+
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.next();
+            return Ok(JsonValue::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.error("expected a quoted field name"));
+            }
+            let key_line = self.line;
+            let key_column = self.column;
+            let key = self.parse_string()?;
+            if members.iter().any(|(existing, _)| *existing == key) {
+                return Err(JsonError {
+                    line: key_line,
+                    column: key_column,
+                    message: format!("duplicate field '{}'", key),
+                });
+            }
+
+            self.skip_whitespace();
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            members.push((key, value));
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.next(),
+                Some('}') => {
+                    self.next();
+                    return Ok(JsonValue::Object(members));
+                }
+                _ => return Err(self.error("expected ',' or '}' after object field")),
+            };
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<JsonValue, JsonError> {
+        self.expect('[')?;
+        let mut elements = vec![];
+
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.next();
+            return Ok(JsonValue::Array(elements));
+        }
+
+        loop {
+            elements.push(self.parse_value()?);
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.next(),
+                Some(']') => {
+                    self.next();
+                    return Ok(JsonValue::Array(elements));
+                }
+                _ => return Err(self.error("expected ',' or ']' after array element")),
+            };
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+        let mut string = String::new();
+
+        loop {
+            match self.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => match self.next() {
+                    Some('"') => string.push('"'),
+                    Some('\\') => string.push('\\'),
+                    Some('/') => string.push('/'),
+                    Some('b') => string.push('\u{8}'),
+                    Some('f') => string.push('\u{c}'),
+                    Some('n') => string.push('\n'),
+                    Some('r') => string.push('\r'),
+                    Some('t') => string.push('\t'),
+                    Some('u') => string.push(self.parse_unicode_escape()?),
+                    _ => return Err(self.error("invalid escape sequence")),
+                },
+                Some(c) if (c as u32) < 0x20 => {
+                    return Err(self.error("control character in string"))
+                }
+                Some(c) => string.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    // Characters outside the Basic Multilingual Plane are escaped as a high surrogate followed by
+    // an escaped low surrogate.
+    fn parse_unicode_escape(&mut self) -> Result<char, JsonError> {
+        let mut code = self.parse_hex_digits()?;
+        if (0xd800..0xdc00).contains(&code) {
+            if self.next() != Some('\\') || self.next() != Some('u') {
+                return Err(self.error("unpaired surrogate in unicode escape"));
+            }
+            let low = self.parse_hex_digits()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(self.error("unpaired surrogate in unicode escape"));
+            }
+            code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+        }
+        char::from_u32(code).ok_or_else(|| self.error("unpaired surrogate in unicode escape"))
+    }
+
+    fn parse_hex_digits(&mut self) -> Result<u32, JsonError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self
+                .next()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| self.error("invalid unicode escape"))?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn parse_number(&mut self) -> Result<f64, JsonError> {
+        let start = self.position;
+        while matches!(self.peek(), Some('-' | '+' | '.' | 'e' | 'E' | '0'..='9')) {
+            self.next();
+        }
+
+        let text: String = self.chars[start..self.position].iter().collect();
+        text.parse()
+            .map_err(|_| self.error(&format!("invalid number '{}'", text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(source: &str) -> (usize, usize, String) {
+        match parse(source) {
+            Err(error) => (error.line, error.column, error.message),
+            Ok(_) => panic!("'{source}' should not parse"),
+        }
+    }
+
+    #[test]
+    fn parses_nested_values() {
+        let json = parse(r#" {"a": [1, -2.5e1, true, null], "b": "x\né"} "#).unwrap();
+        let JsonValue::Object(members) = &json.value else {
+            panic!("expected an object");
+        };
+        assert_eq!(members[0].0, "a");
+        let JsonValue::Array(elements) = &members[0].1.value else {
+            panic!("expected an array");
+        };
+        assert!(matches!(elements[1].value, JsonValue::Number(n) if n == -25.0));
+        assert!(matches!(elements[2].value, JsonValue::Bool(true)));
+        assert!(matches!(elements[3].value, JsonValue::Null));
+        assert!(matches!(&members[1].1.value, JsonValue::String(s) if s == "x\né"));
+    }
+
+    #[test]
+    fn records_where_each_value_starts() {
+        let json = parse("{\n  \"a\": [1,\n    22]\n}").unwrap();
+        assert_eq!((json.line, json.column), (1, 1));
+
+        let JsonValue::Object(members) = &json.value else {
+            panic!("expected an object");
+        };
+        let array = &members[0].1;
+        assert_eq!((array.line, array.column), (2, 8));
+        let JsonValue::Array(elements) = &array.value else {
+            panic!("expected an array");
+        };
+        assert_eq!((elements[1].line, elements[1].column), (3, 5));
+    }
+
+    #[test]
+    fn reports_line_and_column_of_syntax_errors() {
+        assert_eq!(
+            error("{\n  \"a\": 1,\n  \"b\" 2\n}"),
+            (3, 7, "expected ':' but found '2'".to_string())
+        );
+        assert_eq!(
+            error("[1, 2"),
+            (1, 6, "expected ',' or ']' after array element".to_string())
+        );
+        assert_eq!(
+            error("[1] x"),
+            (1, 5, "unexpected trailing characters".to_string())
+        );
+        assert_eq!(
+            error("tru"),
+            (1, 4, "invalid literal, expected 'true'".to_string())
+        );
+        assert_eq!(error("\"abc"), (1, 5, "unterminated string".to_string()));
+        assert_eq!(error("1.2.3"), (1, 6, "invalid number '1.2.3'".to_string()));
+    }
+
+    #[test]
+    fn reports_duplicate_fields_at_the_second_key() {
+        assert_eq!(
+            error("{\"a\": 1,\n \"a\": 2}"),
+            (2, 2, "duplicate field 'a'".to_string())
+        );
+    }
+
+    #[test]
+    fn combines_surrogate_pairs() {
+        let json = parse(r#""\ud83d\ude00 \u00e9""#).unwrap();
+        assert!(matches!(&json.value, JsonValue::String(s) if s == "\u{1f600} é"));
+
+        for source in [r#""\ud83d""#, r#""\ud83dA""#, r#""\ude00""#] {
+            let (_, _, message) = error(source);
+            assert_eq!(message, "unpaired surrogate in unicode escape");
+        }
+    }
+
+    #[test]
+    fn limits_the_nesting_depth() {
+        let nested = |depth| "[".repeat(depth) + &"]".repeat(depth);
+        assert!(parse(&nested(MAX_NESTING_DEPTH)).is_ok());
+        assert_eq!(
+            error(&nested(MAX_NESTING_DEPTH + 1)),
+            (
+                1,
+                MAX_NESTING_DEPTH + 1,
+                "arrays and objects are nested too deeply".to_string()
+            )
+        );
+        assert!(parse(&"{\"a\": ".repeat(100_000)).is_err());
+    }
+}
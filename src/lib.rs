@@ -0,0 +1,115 @@
+//! A path tracer based on "Ray Tracing in One Weekend".
+//!
+//! Build a world out of [`Hittable`] objects, describe the view with a [`Camera`] and render it
+//! into an [`Image`], which the writers in [`output`] serialize to disk.
+//!
+//! ```no_run
+//! use raytracer::{Camera, Color, HittableList, Lambertian, Point3, RenderSettings, Sphere};
+//!
+//! let mut world = HittableList::new();
+//! world.add(Box::new(Sphere::new(
+//!     Point3::new(0.0, 0.0, -1.0),
+//!     0.5,
+//!     Box::new(Lambertian::new(Color::new(0.1, 0.2, 0.5))),
+//! )));
+//!
+//! let camera = Camera::builder()
+//!     .settings(RenderSettings::new(400, 225, 100, 50).unwrap())
+//!     .look_from(Point3::new(0.0, 0.0, 1.0))
+//!     .build()
+//!     .unwrap();
+//! let image = camera.render(&world, 4, Some(1), |finished, total| {
+//!     eprintln!("{} of {} tiles done", finished, total);
+//! });
+//! ```
+//!
+//! New materials implement [`Material`]. This one sends every ray back the way it came:
+//!
+//! ```
+//! use raytracer::{
+//!     Color, Float, HitRecord, Hittable, Interval, Material, Point3, Ray, ScatterResult, Sphere,
+//!     Vector3,
+//! };
+//!
+//! struct Retroreflector {
+//!     albedo: Color,
+//! }
+//!
+//! impl Material for Retroreflector {
+//!     fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> Option<ScatterResult> {
+//!         let scattered = Ray::new(hit_record.point, -ray_in.direction);
+//!         Some(ScatterResult::new(scattered, self.albedo))
+//!     }
+//! }
+//!
+//! let material = Retroreflector {
+//!     albedo: Color::new(0.9, 0.9, 0.9),
+//! };
+//! let sphere = Sphere::new(Point3::new(0.0, 0.0, -2.0), 0.5, Box::new(material));
+//!
+//! let ray = Ray::new(Point3::origin(), Vector3::new(0.0, 0.0, -1.0));
+//! let hit = sphere.hit(&ray, Interval::new(0.001, Float::INFINITY)).unwrap();
+//! let scatter = hit.material.scatter(&ray, &hit).unwrap();
+//! assert!(scatter.scattered.direction == Vector3::new(0.0, 0.0, 1.0));
+//! ```
+
+pub mod output;
+
+mod aabb;
+mod bvh;
+mod camera;
+mod color;
+mod distribution;
+mod environment;
+mod float;
+mod hit;
+mod image;
+mod input;
+mod interval;
+mod json;
+mod material;
+mod matrix;
+mod mesh;
+mod model;
+mod normal;
+mod point;
+mod quad;
+mod quaternion;
+mod ray;
+mod scene;
+mod sphere;
+mod transform;
+mod triangle;
+mod utility;
+mod vector;
+
+pub use crate::aabb::Aabb;
+pub use crate::bvh::BvhNode;
+pub use crate::camera::{
+    Camera, CameraBuilder, CameraLens, CameraPose, CameraPoseError, RenderSettings,
+    RenderSettingsError,
+};
+pub use crate::color::Color;
+pub use crate::environment::{
+    Environment, EnvironmentLight, EnvironmentSample, Gradient, ImageEnvironment, SolidColor,
+};
+pub use crate::float::Float;
+pub use crate::hit::{HitRecord, Hittable, HittableList};
+pub use crate::image::Image;
+pub use crate::input::InputError;
+pub use crate::interval::Interval;
+pub use crate::material::{Dielectric, DiffuseLight, Lambertian, Material, Metal, ScatterResult};
+pub use crate::matrix::{Matrix3, Matrix4};
+pub use crate::mesh::{Mesh, MeshData, MeshFace};
+pub use crate::model::{load_model, ModelError};
+pub use crate::normal::Normal3;
+pub use crate::output::{write_image, writer_for_format, writer_for_path, ImageWriter};
+pub use crate::point::Point3;
+pub use crate::quad::{make_box, PlanarShape, Quad};
+pub use crate::quaternion::Quaternion;
+pub use crate::ray::Ray;
+pub use crate::scene::{load_scene, parse_scene, Scene, SceneError};
+pub use crate::sphere::Sphere;
+pub use crate::transform::{Instance, Transform};
+pub use crate::triangle::Triangle;
+pub use crate::vector::Vector3;
@@ -1,53 +1,84 @@
-use crate::camera::Camera;
-use crate::hit::HittableList;
-use crate::material::{Lambertian, Metal};
-use crate::sphere::Sphere;
-use crate::vector::Vector3;
-
-mod camera;
-mod hit;
-mod interval;
-mod material;
-mod ray;
-mod sphere;
-mod utility;
-mod vector;
-
-fn main() {
-    let mut world = HittableList::new();
-
-    let material_ground = Box::new(Lambertian::new(Vector3::new(0.8, 0.8, 0.0)));
-    let material_center = Box::new(Lambertian::new(Vector3::new(0.1, 0.2, 0.5)));
-    let material_left = Box::new(Metal::new(Vector3::new(0.8, 0.8, 0.8)));
-    let material_right = Box::new(Metal::new(Vector3::new(0.8, 0.6, 0.2)));
-
-    world.add(Box::new(Sphere::new(
-        Vector3::new(0.0, -100.5, -1.0),
-        100.0,
-        material_ground,
-    )));
-    world.add(Box::new(Sphere::new(
-        Vector3::new(0.0, 0.0, -1.2),
-        0.5,
-        material_center,
-    )));
-    world.add(Box::new(Sphere::new(
-        Vector3::new(-1.0, 0.0, -1.0),
-        0.5,
-        material_left,
-    )));
-    world.add(Box::new(Sphere::new(
-        Vector3::new(1.0, 0.0, -1.0),
-        0.5,
-        material_right,
-    )));
-
-    let aspect_ratio = 16.0 / 9.0;
-    let image_width: u16 = 400;
-    let samples_per_pixel: u8 = 100;
-    let max_depth: usize = 50;
-
-    let camera = Camera::new(aspect_ratio, image_width, samples_per_pixel, max_depth);
-
-    camera.render(&world);
+use crate::cli::{parse_args, Command, RenderArgs, HELP};
+use raytracer::{
+    load_scene, write_image, writer_for_format, writer_for_path, BvhNode, Camera, Float,
+    RenderSettings,
+};
+use std::env;
+use std::process::ExitCode;
+use std::thread;
+
+mod cli;
+
+fn main() -> ExitCode {
+    match parse_args(env::args().skip(1)) {
+        Ok(Command::Help) => {
+            print!("{}", HELP);
+            ExitCode::SUCCESS
+        }
+        Ok(Command::Render(args)) => match render(args) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(message) => {
+                eprintln!("error: {}", message);
+                ExitCode::FAILURE
+            }
+        },
+        Err(error) => {
+            eprintln!("error: {}\n\nFor more information, try '--help'.", error);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn render(args: RenderArgs) -> Result<(), String> {
+    let writer = match &args.format {
+        Some(format) => writer_for_format(format),
+        None => writer_for_path(&args.output_path),
+    }
+    .map_err(|error| error.to_string())?;
+
+    let scene = load_scene(&args.scene_path)
+        .map_err(|error| format!("{}: {}", args.scene_path.display(), error))?;
+
+    let scene_settings = &scene.render;
+    let samples_per_pixel = args
+        .samples_per_pixel
+        .unwrap_or(scene_settings.samples_per_pixel());
+    let max_depth = args.max_depth.unwrap_or(scene_settings.max_depth());
+    let settings = match (args.image_width, args.image_height) {
+        (Some(width), Some(height)) => {
+            RenderSettings::new(width, height, samples_per_pixel, max_depth)
+        }
+        (Some(width), None) => RenderSettings::from_aspect_ratio(
+            width,
+            scene_settings.aspect_ratio(),
+            samples_per_pixel,
+            max_depth,
+        ),
+        (None, Some(height)) => {
+            let width = (height as Float * scene_settings.aspect_ratio()).round();
+            RenderSettings::new(width as u32, height, samples_per_pixel, max_depth)
+        }
+        (None, None) => RenderSettings::new(
+            scene_settings.image_width(),
+            scene_settings.image_height(),
+            samples_per_pixel,
+            max_depth,
+        ),
+    }
+    .map_err(|error| error.to_string())?;
+
+    let thread_count = args
+        .thread_count
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |count| count.get()));
+
+    let camera = Camera::new(settings, scene.pose, scene.lens, scene.environment)
+        .map_err(|error| error.to_string())?;
+    let world = BvhNode::new(scene.world);
+
+    let image = camera.render(&world, thread_count, args.seed, |finished, total| {
+        eprint!("\rTiles remaining: {} ", total - finished);
+    });
+    eprintln!("\rDone.                 \n");
+
+    write_image(&*writer, &image, &args.output_path).map_err(|error| error.to_string())
 }
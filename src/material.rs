@@ -1,14 +1,19 @@
+use crate::color::Color;
+use crate::float::consts::PI;
+use crate::float::Float;
 use crate::hit::HitRecord;
 use crate::ray::Ray;
-use crate::vector::{random_unit_vector, reflect, Vector3};
+use crate::utility::{random_from_range, CLOSEST_TO_ZERO_TO_ONE_RANGE};
+use crate::vector::{random_unit_vector, reflect, refract, unit_vector, Vector3};
+use std::sync::Arc;
 
 pub struct ScatterResult {
     pub scattered: Ray,
-    pub attenuated: Vector3,
+    pub attenuated: Color,
 }
 
 impl ScatterResult {
-    pub fn new(scattered: Ray, attenuated: Vector3) -> Self {
+    pub fn new(scattered: Ray, attenuated: Color) -> Self {
         Self {
             scattered,
             attenuated,
@@ -16,49 +21,160 @@ impl ScatterResult {
     }
 }
 
-pub trait Material {
-    fn scatter(&self, hit_record: HitRecord) -> Option<ScatterResult>;
+pub trait Material: Send + Sync {
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> Option<ScatterResult>;
+
+    fn emitted(&self, _hit_record: &HitRecord) -> Color {
+        Color::black()
+    }
+
+    // Density with which `scatter` picks `direction`, for materials whose attenuation does not
+    // depend on the direction. Specular materials return zero and are skipped by light sampling.
+    fn scattering_pdf(&self, _hit_record: &HitRecord, _direction: Vector3) -> Float {
+        0.0
+    }
+}
+
+// Lets several objects share one material.
+impl<M: Material + ?Sized> Material for Arc<M> {
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> Option<ScatterResult> {
+        (**self).scatter(ray_in, hit_record)
+    }
+
+    fn emitted(&self, hit_record: &HitRecord) -> Color {
+        (**self).emitted(hit_record)
+    }
+
+    fn scattering_pdf(&self, hit_record: &HitRecord, direction: Vector3) -> Float {
+        (**self).scattering_pdf(hit_record, direction)
+    }
 }
 
 pub struct Lambertian {
-    albedo: Vector3,
+    albedo: Color,
 }
 
 impl Lambertian {
-    pub fn new(albedo: Vector3) -> Self {
+    pub fn new(albedo: Color) -> Self {
         Self { albedo }
     }
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, hit_record: HitRecord) -> Option<ScatterResult> {
-        let mut scatter_direction = &hit_record.normal + random_unit_vector();
+    fn scatter(&self, _ray_in: &Ray, hit_record: &HitRecord) -> Option<ScatterResult> {
+        let mut scatter_direction = hit_record.normal.to_vector() + random_unit_vector();
 
         if scatter_direction.is_near_zero() {
-            scatter_direction = hit_record.normal.clone();
+            scatter_direction = hit_record.normal.to_vector();
         };
 
         let scattered_ray = Ray::new(hit_record.point, scatter_direction);
-        let attenuation = self.albedo.clone();
+        // Vertex colours, where the surface has them, tint the albedo.
+        let attenuation = match hit_record.vertex_color {
+            Some(color) => self.albedo * color,
+            None => self.albedo,
+        };
         Some(ScatterResult::new(scattered_ray, attenuation))
     }
+
+    fn scattering_pdf(&self, hit_record: &HitRecord, direction: Vector3) -> Float {
+        let cosine = hit_record.normal.dot(unit_vector(direction));
+        if cosine > 0.0 {
+            cosine / PI
+        } else {
+            0.0
+        }
+    }
 }
 
 pub struct Metal {
-    albedo: Vector3,
+    albedo: Color,
+    fuzz: Float,
 }
 
 impl Metal {
-    pub fn new(albedo: Vector3) -> Self {
-        Self { albedo }
+    pub fn new(albedo: Color, fuzz: Float) -> Self {
+        Self {
+            albedo,
+            fuzz: fuzz.clamp(0.0, 1.0),
+        }
     }
 }
 
 impl Material for Metal {
-    fn scatter(&self, hit_record: HitRecord) -> Option<ScatterResult> {
-        let reflected = reflect(&hit_record.normal, &self.albedo);
-        let scattered_ray = Ray::new(hit_record.point, reflected);
-        let attenuation = self.albedo.clone();
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> Option<ScatterResult> {
+        let reflected = reflect(ray_in.direction, hit_record.normal);
+        let reflected = unit_vector(reflected) + (self.fuzz * random_unit_vector());
+        if hit_record.normal.dot(reflected) > 0.0 {
+            let scattered_ray = Ray::new(hit_record.point, reflected);
+            let attenuation = self.albedo;
+            Some(ScatterResult::new(scattered_ray, attenuation))
+        } else {
+            None
+        }
+    }
+}
+
+pub struct Dielectric {
+    refraction_index: Float,
+}
+
+impl Dielectric {
+    pub fn new(refraction_index: Float) -> Self {
+        Self { refraction_index }
+    }
+
+    fn reflectance(cosine: Float, refraction_index: Float) -> Float {
+        // Schlick's approximation for reflectance.
+        let r0 = (1.0 - refraction_index) / (1.0 + refraction_index);
+        let r0 = r0 * r0;
+        r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
+    }
+}
+
+impl Material for Dielectric {
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> Option<ScatterResult> {
+        let ri = if hit_record.is_front_face {
+            1.0 / self.refraction_index
+        } else {
+            self.refraction_index
+        };
+
+        let unit_direction = unit_vector(ray_in.direction);
+        let cos_theta = (-hit_record.normal.dot(unit_direction)).min(1.0);
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+
+        let cannot_refract = ri * sin_theta > 1.0;
+        let direction = if cannot_refract
+            || Self::reflectance(cos_theta, ri) > random_from_range(CLOSEST_TO_ZERO_TO_ONE_RANGE)
+        {
+            reflect(unit_direction, hit_record.normal)
+        } else {
+            refract(unit_direction, hit_record.normal, ri)
+        };
+
+        let scattered_ray = Ray::new(hit_record.point, direction);
+        let attenuation = Color::white();
         Some(ScatterResult::new(scattered_ray, attenuation))
     }
 }
+
+pub struct DiffuseLight {
+    emit: Color,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Color) -> Self {
+        Self { emit }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(&self, _ray_in: &Ray, _hit_record: &HitRecord) -> Option<ScatterResult> {
+        None
+    }
+
+    fn emitted(&self, _hit_record: &HitRecord) -> Color {
+        self.emit
+    }
+}
@@ -0,0 +1,468 @@
+use crate::float::Float;
+use crate::normal::Normal3;
+use crate::point::Point3;
+use crate::vector::{unit_vector, Vector3};
+use std::ops::Mul;
+
+// How small the determinant may get relative to the product of the column lengths before a
+// matrix counts as singular. By Hadamard's inequality that product bounds the determinant, and
+// their ratio depends only on how close the columns are to being dependent, not on the scale of
+// the matrix, so tiny and huge transformations are judged alike.
+const SINGULAR_TOLERANCE: Float = 16.0 * Float::EPSILON;
+
+fn is_singular(determinant: Float, column_lengths: &[Float]) -> bool {
+    let ratio = determinant.abs() / column_lengths.iter().product::<Float>();
+    ratio.is_nan() || ratio <= SINGULAR_TOLERANCE
+}
+
+// A row-major linear map acting on column vectors.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix3 {
+    pub rows: [[Float; 3]; 3],
+}
+
+impl Matrix3 {
+    pub fn new(rows: [[Float; 3]; 3]) -> Self {
+        Self { rows }
+    }
+
+    pub fn identity() -> Self {
+        Self::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    pub fn from_columns(x: Vector3, y: Vector3, z: Vector3) -> Self {
+        Self::new([[x.x, y.x, z.x], [x.y, y.y, z.y], [x.z, y.z, z.z]])
+    }
+
+    pub fn transpose(&self) -> Self {
+        let m = &self.rows;
+        Self::new([
+            [m[0][0], m[1][0], m[2][0]],
+            [m[0][1], m[1][1], m[2][1]],
+            [m[0][2], m[1][2], m[2][2]],
+        ])
+    }
+
+    pub fn column(&self, index: usize) -> Vector3 {
+        let m = &self.rows;
+        Vector3::new(m[0][index], m[1][index], m[2][index])
+    }
+
+    pub fn determinant(&self) -> Float {
+        let m = &self.rows;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    // The adjugate divided by the determinant. Returns `None` for singular matrices.
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        if is_singular(determinant, &[0, 1, 2].map(|i| self.column(i).length())) {
+            return None;
+        }
+
+        let m = &self.rows;
+        let cofactor = |r0: usize, r1: usize, c0: usize, c1: usize| {
+            (m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]) / determinant
+        };
+        Some(Self::new([
+            [
+                cofactor(1, 2, 1, 2),
+                -cofactor(0, 2, 1, 2),
+                cofactor(0, 1, 1, 2),
+            ],
+            [
+                -cofactor(1, 2, 0, 2),
+                cofactor(0, 2, 0, 2),
+                -cofactor(0, 1, 0, 2),
+            ],
+            [
+                cofactor(1, 2, 0, 1),
+                -cofactor(0, 2, 0, 1),
+                cofactor(0, 1, 0, 1),
+            ],
+        ]))
+    }
+
+    pub fn transform(&self, vector: Vector3) -> Vector3 {
+        let m = &self.rows;
+        Vector3::new(
+            m[0][0] * vector.x + m[0][1] * vector.y + m[0][2] * vector.z,
+            m[1][0] * vector.x + m[1][1] * vector.y + m[1][2] * vector.z,
+            m[2][0] * vector.x + m[2][1] * vector.y + m[2][2] * vector.z,
+        )
+    }
+
+    // The matrix that maps normals, which must stay perpendicular to transformed surfaces.
+    pub fn normal_matrix(&self) -> Option<Self> {
+        Some(self.inverse()?.transpose())
+    }
+}
+
+impl Default for Matrix3 {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Mul for &Matrix3 {
+    type Output = Matrix3;
+
+    fn mul(self, other: &Matrix3) -> Matrix3 {
+        let mut rows = [[0.0; 3]; 3];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..3).map(|k| self.rows[i][k] * other.rows[k][j]).sum();
+            }
+        }
+        Matrix3::new(rows)
+    }
+}
+
+impl Mul for Matrix3 {
+    type Output = Matrix3;
+
+    fn mul(self, other: Matrix3) -> Matrix3 {
+        &self * &other
+    }
+}
+
+// A row-major affine transformation acting on column vectors.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix4 {
+    pub rows: [[Float; 4]; 4],
+}
+
+impl Matrix4 {
+    pub fn new(rows: [[Float; 4]; 4]) -> Self {
+        Self { rows }
+    }
+
+    // Combines a linear map with a translation applied after it.
+    pub fn from_linear(linear: &Matrix3, translation: Vector3) -> Self {
+        let m = &linear.rows;
+        Self::new([
+            [m[0][0], m[0][1], m[0][2], translation.x],
+            [m[1][0], m[1][1], m[1][2], translation.y],
+            [m[2][0], m[2][1], m[2][2], translation.z],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn identity() -> Self {
+        Self::new([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn translation(offset: Vector3) -> Self {
+        Self::new([
+            [1.0, 0.0, 0.0, offset.x],
+            [0.0, 1.0, 0.0, offset.y],
+            [0.0, 0.0, 1.0, offset.z],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn scaling(factors: Vector3) -> Self {
+        Self::new([
+            [factors.x, 0.0, 0.0, 0.0],
+            [0.0, factors.y, 0.0, 0.0],
+            [0.0, 0.0, factors.z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    // Counter-clockwise rotation around `axis` when looking against it.
+    pub fn rotation(axis: Vector3, degrees: Float) -> Self {
+        let Vector3 { x, y, z } = unit_vector(axis);
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let t = 1.0 - cos;
+        Self::new([
+            [
+                t * x * x + cos,
+                t * x * y - sin * z,
+                t * x * z + sin * y,
+                0.0,
+            ],
+            [
+                t * x * y + sin * z,
+                t * y * y + cos,
+                t * y * z - sin * x,
+                0.0,
+            ],
+            [
+                t * x * z - sin * y,
+                t * y * z + sin * x,
+                t * z * z + cos,
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut rows = [[0.0; 4]; 4];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.rows[j][i];
+            }
+        }
+        Self::new(rows)
+    }
+
+    // Returns `None` for singular matrices. Affine matrices are inverted through their linear
+    // part so that they agree with `Matrix3::inverse`; others use Gauss-Jordan elimination with
+    // partial pivoting.
+    pub fn inverse(&self) -> Option<Self> {
+        if self.rows[3] == [0.0, 0.0, 0.0, 1.0] {
+            let linear = self.linear().inverse()?;
+            let translation = Vector3::new(self.rows[0][3], self.rows[1][3], self.rows[2][3]);
+            return Some(Self::from_linear(&linear, -linear.transform(translation)));
+        }
+
+        let column_lengths = [0, 1, 2, 3].map(|j| {
+            (0..4)
+                .map(|i| self.rows[i][j] * self.rows[i][j])
+                .sum::<Float>()
+                .sqrt()
+        });
+        let mut left = self.rows;
+        let mut right = Self::identity().rows;
+        let mut determinant = 1.0;
+
+        for column in 0..4 {
+            let pivot = (column..4)
+                .max_by(|&a, &b| left[a][column].abs().total_cmp(&left[b][column].abs()))
+                .unwrap_or(column);
+            if left[pivot][column] == 0.0 {
+                return None;
+            }
+            if pivot != column {
+                determinant = -determinant;
+            }
+            left.swap(column, pivot);
+            right.swap(column, pivot);
+            determinant *= left[column][column];
+
+            let scale = 1.0 / left[column][column];
+            for j in 0..4 {
+                left[column][j] *= scale;
+                right[column][j] *= scale;
+            }
+
+            for row in 0..4 {
+                if row != column {
+                    let factor = left[row][column];
+                    for j in 0..4 {
+                        left[row][j] -= factor * left[column][j];
+                        right[row][j] -= factor * right[column][j];
+                    }
+                }
+            }
+        }
+
+        if is_singular(determinant, &column_lengths) {
+            return None;
+        }
+        Some(Self::new(right))
+    }
+
+    // The upper-left 3x3 part, which holds everything but the translation.
+    pub fn linear(&self) -> Matrix3 {
+        let m = &self.rows;
+        Matrix3::new([
+            [m[0][0], m[0][1], m[0][2]],
+            [m[1][0], m[1][1], m[1][2]],
+            [m[2][0], m[2][1], m[2][2]],
+        ])
+    }
+
+    pub fn transform_point(&self, point: Point3) -> Point3 {
+        let m = &self.rows;
+        Point3::new(
+            m[0][0] * point.x + m[0][1] * point.y + m[0][2] * point.z + m[0][3],
+            m[1][0] * point.x + m[1][1] * point.y + m[1][2] * point.z + m[1][3],
+            m[2][0] * point.x + m[2][1] * point.y + m[2][2] * point.z + m[2][3],
+        )
+    }
+
+    // Ignores the translation, as directions have no position.
+    pub fn transform_vector(&self, vector: Vector3) -> Vector3 {
+        let m = &self.rows;
+        Vector3::new(
+            m[0][0] * vector.x + m[0][1] * vector.y + m[0][2] * vector.z,
+            m[1][0] * vector.x + m[1][1] * vector.y + m[1][2] * vector.z,
+            m[2][0] * vector.x + m[2][1] * vector.y + m[2][2] * vector.z,
+        )
+    }
+
+    // Applies the inverse transpose and renormalizes. Callers transforming many
+    // normals should keep `linear().normal_matrix()` around instead.
+    pub fn transform_normal(&self, normal: Normal3) -> Option<Normal3> {
+        let transformed = self.linear().normal_matrix()?.transform(normal.to_vector());
+        Some(Normal3::new(transformed))
+    }
+}
+
+impl Default for Matrix4 {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Mul for &Matrix4 {
+    type Output = Matrix4;
+
+    fn mul(self, other: &Matrix4) -> Matrix4 {
+        let mut rows = [[0.0; 4]; 4];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.rows[i][k] * other.rows[k][j]).sum();
+            }
+        }
+        Matrix4::new(rows)
+    }
+}
+
+impl Mul for Matrix4 {
+    type Output = Matrix4;
+
+    fn mul(self, other: Matrix4) -> Matrix4 {
+        &self * &other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::dot;
+
+    fn assert_close(actual: Float, expected: Float) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    fn assert_identity3(matrix: &Matrix3) {
+        for (i, row) in matrix.rows.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                assert_close(value, if i == j { 1.0 } else { 0.0 });
+            }
+        }
+    }
+
+    fn assert_identity4(matrix: &Matrix4) {
+        for (i, row) in matrix.rows.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                assert_close(value, if i == j { 1.0 } else { 0.0 });
+            }
+        }
+    }
+
+    fn affine() -> Matrix4 {
+        Matrix4::translation(Vector3::new(3.0, -2.0, 5.0))
+            * Matrix4::rotation(Vector3::new(1.0, 2.0, -0.5), 37.0)
+            * Matrix4::scaling(Vector3::new(2.0, 0.5, 3.0))
+    }
+
+    #[test]
+    fn matrix3_inverse_round_trips() {
+        let matrix = Matrix3::new([[2.0, -1.0, 0.5], [0.0, 3.0, 1.0], [4.0, 1.0, -2.0]]);
+        let inverse = matrix.inverse().unwrap();
+        assert_identity3(&(&matrix * &inverse));
+        assert_identity3(&(&inverse * &matrix));
+    }
+
+    #[test]
+    fn matrix4_inverse_round_trips() {
+        let matrix = affine();
+        assert_identity4(&(&matrix * &matrix.inverse().unwrap()));
+
+        // A projective matrix takes the general elimination path.
+        let projective = Matrix4::new([
+            [1.0, 2.0, 0.0, 1.0],
+            [0.0, 1.0, 3.0, 0.0],
+            [2.0, 0.0, 1.0, 1.0],
+            [0.0, 0.0, 1.0, 2.0],
+        ]);
+        assert_identity4(&(&projective * &projective.inverse().unwrap()));
+    }
+
+    #[test]
+    fn singular_matrices_have_no_inverse() {
+        let dependent_rows = Matrix3::new([[1.0, 2.0, 3.0], [2.0, 4.0, 6.0], [0.0, 0.0, 1.0]]);
+        assert_eq!(dependent_rows.inverse(), None);
+        assert_eq!(dependent_rows.normal_matrix(), None);
+        assert_eq!(Matrix3::new([[0.0; 3]; 3]).inverse(), None);
+
+        assert_eq!(
+            Matrix4::scaling(Vector3::new(1.0, 0.0, 1.0)).inverse(),
+            None
+        );
+        let projective = Matrix4::new([
+            [1.0, 2.0, 0.0, 1.0],
+            [2.0, 4.0, 0.0, 2.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0, 2.0],
+        ]);
+        assert_eq!(projective.inverse(), None);
+    }
+
+    #[test]
+    fn singularity_does_not_depend_on_scale() {
+        for factor in [1e-4, 1e4] {
+            let matrix = Matrix4::scaling(Vector3::new(factor, factor, factor));
+            let inverse = matrix.inverse().unwrap();
+            assert_close(inverse.rows[0][0] * factor, 1.0);
+            assert!(matrix.linear().normal_matrix().is_some());
+        }
+    }
+
+    #[test]
+    fn transposes() {
+        let matrix = Matrix3::new([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+        assert_eq!(
+            matrix.transpose(),
+            Matrix3::new([[1.0, 4.0, 7.0], [2.0, 5.0, 8.0], [3.0, 6.0, 9.0]])
+        );
+        assert_eq!(affine().transpose().transpose(), affine());
+        assert_eq!(affine().transpose().rows[3][0], 3.0);
+    }
+
+    #[test]
+    fn vectors_ignore_translation() {
+        let matrix = Matrix4::translation(Vector3::new(1.0, 2.0, 3.0))
+            * Matrix4::scaling(Vector3::new(2.0, 2.0, 2.0));
+        assert_eq!(
+            matrix.transform_point(Point3::new(1.0, 0.0, 0.0)),
+            Point3::new(3.0, 2.0, 3.0)
+        );
+        assert_eq!(
+            matrix.transform_vector(Vector3::new(1.0, 0.0, 0.0)),
+            Vector3::new(2.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn normals_stay_perpendicular_under_non_uniform_scale() {
+        let matrix = Matrix4::scaling(Vector3::new(4.0, 1.0, 0.25))
+            * Matrix4::rotation(Vector3::new(0.0, 0.0, 1.0), 30.0);
+        let tangent = Vector3::new(1.0, -1.0, 2.0);
+        let normal = Normal3::new(Vector3::new(1.0, 1.0, 0.0));
+        assert_close(normal.dot(tangent), 0.0);
+
+        let transformed = matrix.transform_normal(normal).unwrap();
+        assert_close(transformed.dot(matrix.transform_vector(tangent)), 0.0);
+        assert_close(transformed.to_vector().length(), 1.0);
+
+        // Transforming the normal like a vector would not keep it perpendicular.
+        let naive = matrix.transform_vector(normal.to_vector());
+        assert!(dot(naive, matrix.transform_vector(tangent)).abs() > 0.1);
+    }
+}
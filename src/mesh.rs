@@ -0,0 +1,238 @@
+use crate::aabb::{Aabb, EMPTY_BOX};
+use crate::color::Color;
+use crate::float::Float;
+use crate::hit::{HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::normal::Normal3;
+use crate::point::Point3;
+use crate::ray::Ray;
+use crate::triangle::{
+    geometric_normal, interpolate_color, interpolate_normal, interpolate_uv, intersect_triangle,
+    triangle_hit_record,
+};
+use std::ops::Range;
+
+const MAX_LEAF_FACES: usize = 4;
+
+// A triangle referencing the shared buffers of its mesh by index.
+pub struct MeshFace {
+    pub positions: [usize; 3],
+    pub normals: Option<[usize; 3]>,
+    pub uvs: Option<[usize; 3]>,
+    pub material: usize,
+    pub group: usize,
+}
+
+// The raw contents of a mesh as produced by the model loaders, before the acceleration
+// structure is built.
+#[derive(Default)]
+pub struct MeshData {
+    pub positions: Vec<Point3>,
+    pub normals: Vec<Normal3>,
+    pub uvs: Vec<(Float, Float)>,
+    // Either empty or one colour per position.
+    pub colors: Vec<Color>,
+    pub faces: Vec<MeshFace>,
+    pub materials: Vec<Box<dyn Material>>,
+    pub groups: Vec<String>,
+}
+
+impl MeshData {
+    // Drops every face that does not belong to one of the named groups.
+    pub fn retain_groups(&mut self, names: &[&str]) {
+        let groups = &self.groups;
+        self.faces
+            .retain(|face| names.contains(&groups[face.group].as_str()));
+    }
+
+    // Replaces the materials of all faces with a single one.
+    pub fn set_material(&mut self, material: Box<dyn Material>) {
+        self.materials = vec![material];
+        for face in &mut self.faces {
+            face.material = 0;
+        }
+    }
+}
+
+// A triangle mesh with its own bounding volume hierarchy over the faces, so a model adds a
+// single object to the world instead of one per triangle.
+pub struct Mesh {
+    data: MeshData,
+    nodes: Vec<MeshNode>,
+}
+
+struct MeshNode {
+    bbox: Aabb,
+    content: MeshNodeContent,
+}
+
+enum MeshNodeContent {
+    Leaf(Range<usize>),
+    // Index of the right child; the left child directly follows its parent.
+    Branch(usize),
+}
+
+struct MeshHit {
+    face: usize,
+    t: Float,
+    b1: Float,
+    b2: Float,
+}
+
+impl Mesh {
+    pub fn new(mut data: MeshData) -> Self {
+        let boxes: Vec<Aabb> = data
+            .faces
+            .iter()
+            .map(|face| face_bounding_box(&data.positions, face))
+            .collect();
+        let mut order: Vec<usize> = (0..data.faces.len()).collect();
+        let mut nodes = Vec::new();
+        build_node(&mut nodes, &boxes, &mut order, 0);
+
+        // Store the faces in leaf order so every leaf covers a contiguous range.
+        let mut faces: Vec<Option<MeshFace>> = data.faces.drain(..).map(Some).collect();
+        data.faces = order
+            .iter()
+            .map(|&index| faces[index].take().unwrap())
+            .collect();
+
+        Self { data, nodes }
+    }
+
+    pub fn face_count(&self) -> usize {
+        self.data.faces.len()
+    }
+
+    fn hit_node(&self, index: usize, ray: &Ray, ray_t: &Interval, closest: &mut Option<MeshHit>) {
+        let node = &self.nodes[index];
+        let closest_so_far = closest.as_ref().map_or(ray_t.max, |hit| hit.t);
+        let ray_t = Interval::new(ray_t.min, closest_so_far);
+        if !node.bbox.hit(ray, &ray_t) {
+            return;
+        }
+
+        match &node.content {
+            MeshNodeContent::Leaf(range) => {
+                for face_index in range.clone() {
+                    let [v0, v1, v2] = self.data.faces[face_index]
+                        .positions
+                        .map(|index| self.data.positions[index]);
+                    let closest_so_far = closest.as_ref().map_or(ray_t.max, |hit| hit.t);
+                    if let Some((t, b1, b2)) = intersect_triangle(
+                        ray,
+                        v0,
+                        v1,
+                        v2,
+                        &Interval::new(ray_t.min, closest_so_far),
+                        false,
+                    ) {
+                        *closest = Some(MeshHit {
+                            face: face_index,
+                            t,
+                            b1,
+                            b2,
+                        });
+                    }
+                }
+            }
+            MeshNodeContent::Branch(right) => {
+                self.hit_node(index + 1, ray, &ray_t, closest);
+                self.hit_node(*right, ray, &ray_t, closest);
+            }
+        }
+    }
+}
+
+impl Hittable for Mesh {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        let mut closest = None;
+        self.hit_node(0, ray, &ray_t, &mut closest);
+        let MeshHit { face, t, b1, b2 } = closest?;
+
+        let face = &self.data.faces[face];
+        let [v0, v1, v2] = face.positions.map(|index| self.data.positions[index]);
+        let uv = match face.uvs {
+            Some(uvs) => interpolate_uv(&uvs.map(|index| self.data.uvs[index]), b1, b2),
+            None => (b1, b2),
+        };
+        let shading_normal = face.normals.map(|normals| {
+            interpolate_normal(normals.map(|index| self.data.normals[index]), b1, b2)
+        });
+
+        let mut record = triangle_hit_record(
+            ray,
+            t,
+            uv,
+            geometric_normal(v0, v1, v2),
+            shading_normal,
+            &*self.data.materials[face.material],
+        );
+        if !self.data.colors.is_empty() {
+            let colors = face.positions.map(|index| self.data.colors[index]);
+            record.vertex_color = Some(interpolate_color(colors, b1, b2));
+        }
+        Some(record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.nodes[0].bbox.clone()
+    }
+}
+
+fn face_bounding_box(positions: &[Point3], face: &MeshFace) -> Aabb {
+    let [v0, v1, v2] = face.positions.map(|index| positions[index]);
+    Aabb::surrounding(&Aabb::from_points(v0, v1), &Aabb::from_points(v2, v2))
+}
+
+// Splits the faces at the median centroid along the axis in which the centroids spread the most.
+fn build_node(nodes: &mut Vec<MeshNode>, boxes: &[Aabb], order: &mut [usize], offset: usize) {
+    let bbox = order.iter().fold(EMPTY_BOX, |bbox, &index| {
+        Aabb::surrounding(&bbox, &boxes[index])
+    });
+    let (axis, spread) = widest_centroid_axis(boxes, order);
+    let node_index = nodes.len();
+    let leaf = MeshNodeContent::Leaf(offset..offset + order.len());
+    nodes.push(MeshNode {
+        bbox,
+        content: leaf,
+    });
+
+    if order.len() <= MAX_LEAF_FACES || spread <= 0.0 {
+        return;
+    }
+
+    let middle = order.len() / 2;
+    order.select_nth_unstable_by(middle, |&a, &b| {
+        boxes[a].centroid()[axis].total_cmp(&boxes[b].centroid()[axis])
+    });
+    let (left, right) = order.split_at_mut(middle);
+    build_node(nodes, boxes, left, offset);
+    let right_index = nodes.len();
+    build_node(nodes, boxes, right, offset + middle);
+    nodes[node_index].content = MeshNodeContent::Branch(right_index);
+}
+
+fn widest_centroid_axis(boxes: &[Aabb], order: &[usize]) -> (usize, Float) {
+    let mut min = [Float::INFINITY; 3];
+    let mut max = [Float::NEG_INFINITY; 3];
+    for &index in order {
+        let centroid = boxes[index].centroid();
+        for axis in 0..3 {
+            min[axis] = min[axis].min(centroid[axis]);
+            max[axis] = max[axis].max(centroid[axis]);
+        }
+    }
+
+    (0..3).map(|axis| (axis, max[axis] - min[axis])).fold(
+        (0, Float::NEG_INFINITY),
+        |widest, candidate| {
+            if candidate.1 > widest.1 {
+                candidate
+            } else {
+                widest
+            }
+        },
+    )
+}
@@ -0,0 +1,61 @@
+use crate::mesh::MeshData;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io::Error as IoError;
+use std::path::{Path, PathBuf};
+
+mod obj;
+mod ply;
+mod stl;
+
+#[derive(Debug)]
+pub enum ModelError {
+    UnsupportedFormat(String),
+    Malformed(String),
+    Io(PathBuf, IoError),
+}
+
+impl Display for ModelError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ModelError::UnsupportedFormat(extension) => {
+                write!(f, "unsupported model format: '{}'", extension)
+            }
+            ModelError::Malformed(reason) => write!(f, "malformed model: {}", reason),
+            ModelError::Io(path, error) => {
+                write!(f, "could not read '{}': {}", path.display(), error)
+            }
+        }
+    }
+}
+
+// Loads the triangles of a model file, picking the format from its extension.
+pub fn load_model(path: &Path) -> Result<MeshData, ModelError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    match extension.as_str() {
+        "obj" => obj::load(path),
+        "ply" => ply::decode(&read(path)?),
+        "stl" => stl::decode(&read(path)?),
+        _ => Err(ModelError::UnsupportedFormat(extension)),
+    }
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, ModelError> {
+    std::fs::read(path).map_err(|error| ModelError::Io(path.to_path_buf(), error))
+}
+
+fn read_to_string(path: &Path) -> Result<String, ModelError> {
+    std::fs::read_to_string(path).map_err(|error| ModelError::Io(path.to_path_buf(), error))
+}
+
+fn parse_number(token: &str) -> Result<f64, String> {
+    token
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())
+        .ok_or_else(|| format!("invalid number '{}'", token))
+}
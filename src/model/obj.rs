@@ -0,0 +1,492 @@
+use crate::color::Color;
+use crate::float::Float;
+use crate::material::{Dielectric, DiffuseLight, Lambertian, Material, Metal};
+use crate::mesh::{MeshData, MeshFace};
+use crate::model::{parse_number, read_to_string, ModelError};
+use crate::normal::Normal3;
+use crate::point::Point3;
+use crate::vector::Vector3;
+use std::collections::HashMap;
+use std::path::Path;
+
+const DEFAULT_GROUP: &str = "default";
+
+// The subset of an MTL material description that maps onto the crate's materials.
+struct MtlMaterial {
+    diffuse: Color,
+    specular: Color,
+    emission: Color,
+    shininess: Float,
+    refraction_index: Option<Float>,
+    dissolve: Float,
+    illumination: u32,
+}
+
+impl Default for MtlMaterial {
+    fn default() -> Self {
+        Self {
+            diffuse: Color::gray(0.8),
+            specular: Color::black(),
+            emission: Color::black(),
+            shininess: 0.0,
+            refraction_index: None,
+            dissolve: 1.0,
+            illumination: 2,
+        }
+    }
+}
+
+impl MtlMaterial {
+    fn to_material(&self) -> Box<dyn Material> {
+        if !self.emission.is_black() {
+            Box::new(DiffuseLight::new(self.emission))
+        } else if self.dissolve < 1.0 || matches!(self.illumination, 4 | 6 | 7 | 9) {
+            Box::new(Dielectric::new(self.refraction_index.unwrap_or(1.5)))
+        } else if matches!(self.illumination, 3 | 5) {
+            // Ns ranges from 0 to 1000, with higher values giving sharper highlights.
+            let fuzz = (1.0 - self.shininess / 1000.0).clamp(0.0, 1.0);
+            Box::new(Metal::new(self.specular, fuzz))
+        } else {
+            Box::new(Lambertian::new(self.diffuse))
+        }
+    }
+}
+
+struct FaceVertex {
+    position: usize,
+    uv: Option<usize>,
+    normal: Option<usize>,
+}
+
+pub fn load(path: &Path) -> Result<MeshData, ModelError> {
+    let source = read_to_string(path)?;
+    parse(&source, path.parent().unwrap_or(Path::new("")))
+}
+
+// Parses a Wavefront OBJ file. Material libraries are resolved against `base_directory`.
+pub fn parse(source: &str, base_directory: &Path) -> Result<MeshData, ModelError> {
+    let mut data = MeshData::default();
+    let mut library: HashMap<String, MtlMaterial> = HashMap::new();
+    let mut material_indices: HashMap<String, usize> = HashMap::new();
+    let mut current_material = None;
+    let mut current_group = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let malformed =
+            |message: String| ModelError::Malformed(format!("line {}: {}", index + 1, message));
+        let (keyword, arguments) = match split_statement(line) {
+            Some(statement) => statement,
+            None => continue,
+        };
+
+        match keyword {
+            "v" => {
+                let Vector3 { x, y, z } = parse_vector(&arguments, 3, 4).map_err(malformed)?;
+                data.positions.push(Point3::new(x, y, z));
+            }
+            "vn" => {
+                let normal = parse_vector(&arguments, 3, 3).map_err(malformed)?;
+                if normal.length_squared() == 0.0 {
+                    return Err(malformed("normal must not be zero".to_string()));
+                }
+                data.normals.push(Normal3::new(normal));
+            }
+            "vt" => {
+                let coordinates = parse_numbers(&arguments, 1, 3).map_err(malformed)?;
+                data.uvs
+                    .push((coordinates[0], coordinates.get(1).copied().unwrap_or(0.0)));
+            }
+            "f" => {
+                let vertices = arguments
+                    .iter()
+                    .map(|token| parse_face_vertex(token, &data))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(malformed)?;
+                if vertices.len() < 3 {
+                    return Err(malformed(format!(
+                        "face needs at least 3 vertices but has {}",
+                        vertices.len()
+                    )));
+                }
+
+                let material = match current_material {
+                    Some(material) => material,
+                    // Faces before any `usemtl` get a plain grey material.
+                    None => {
+                        data.materials.push(MtlMaterial::default().to_material());
+                        current_material = Some(data.materials.len() - 1);
+                        data.materials.len() - 1
+                    }
+                };
+                let group = match current_group {
+                    Some(group) => group,
+                    None => {
+                        let group = group_index(&mut data, DEFAULT_GROUP);
+                        current_group = Some(group);
+                        group
+                    }
+                };
+
+                // Polygons are split into a fan around their first vertex, which is exact for the
+                // convex faces that modelling tools export.
+                for i in 1..vertices.len() - 1 {
+                    let corners = [&vertices[0], &vertices[i], &vertices[i + 1]];
+                    data.faces.push(MeshFace {
+                        positions: corners.map(|vertex| vertex.position),
+                        normals: match corners.map(|vertex| vertex.normal) {
+                            [Some(a), Some(b), Some(c)] => Some([a, b, c]),
+                            _ => None,
+                        },
+                        uvs: match corners.map(|vertex| vertex.uv) {
+                            [Some(a), Some(b), Some(c)] => Some([a, b, c]),
+                            _ => None,
+                        },
+                        material,
+                        group,
+                    });
+                }
+            }
+            "g" | "o" => {
+                let name = if arguments.is_empty() {
+                    DEFAULT_GROUP.to_string()
+                } else {
+                    arguments.join(" ")
+                };
+                current_group = Some(group_index(&mut data, &name));
+            }
+            "usemtl" => {
+                let name = arguments.join(" ");
+                let material = match material_indices.get(&name) {
+                    Some(&material) => material,
+                    None => {
+                        let material = library
+                            .get(&name)
+                            .ok_or_else(|| malformed(format!("unknown material '{}'", name)))?;
+                        data.materials.push(material.to_material());
+                        material_indices.insert(name, data.materials.len() - 1);
+                        data.materials.len() - 1
+                    }
+                };
+                current_material = Some(material);
+            }
+            "mtllib" => {
+                for file in &arguments {
+                    let path = base_directory.join(file);
+                    let source = read_to_string(&path)?;
+                    parse_mtl(&source, &mut library).map_err(|message| {
+                        ModelError::Malformed(format!("{}: {}", path.display(), message))
+                    })?;
+                }
+            }
+            // Smoothing groups, lines, points and free-form geometry are not rendered.
+            _ => {}
+        }
+    }
+
+    Ok(data)
+}
+
+fn parse_mtl(source: &str, library: &mut HashMap<String, MtlMaterial>) -> Result<(), String> {
+    let mut current: Option<(String, MtlMaterial)> = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let malformed = |message: String| format!("line {}: {}", index + 1, message);
+        let (keyword, arguments) = match split_statement(line) {
+            Some(statement) => statement,
+            None => continue,
+        };
+
+        if keyword == "newmtl" {
+            if let Some((name, material)) = current.take() {
+                library.insert(name, material);
+            }
+            current = Some((arguments.join(" "), MtlMaterial::default()));
+            continue;
+        }
+
+        let material = match &mut current {
+            Some((_, material)) => material,
+            None => return Err(malformed(format!("'{}' before newmtl", keyword))),
+        };
+        match keyword {
+            "Kd" => material.diffuse = parse_color(&arguments).map_err(malformed)?,
+            "Ks" => material.specular = parse_color(&arguments).map_err(malformed)?,
+            "Ke" => material.emission = parse_color(&arguments).map_err(malformed)?,
+            "Ns" => material.shininess = parse_numbers(&arguments, 1, 1).map_err(malformed)?[0],
+            "Ni" => {
+                material.refraction_index =
+                    Some(parse_numbers(&arguments, 1, 1).map_err(malformed)?[0])
+            }
+            "d" => material.dissolve = parse_numbers(&arguments, 1, 1).map_err(malformed)?[0],
+            "Tr" => {
+                material.dissolve = 1.0 - parse_numbers(&arguments, 1, 1).map_err(malformed)?[0]
+            }
+            "illum" => {
+                material.illumination = arguments
+                    .first()
+                    .and_then(|token| token.parse().ok())
+                    .ok_or_else(|| malformed("invalid illumination model".to_string()))?
+            }
+            // Texture maps and the remaining parameters have no equivalent in the renderer.
+            _ => {}
+        }
+    }
+
+    if let Some((name, material)) = current {
+        library.insert(name, material);
+    }
+    Ok(())
+}
+
+// Splits a line into its keyword and arguments, skipping blank lines and comments.
+fn split_statement(line: &str) -> Option<(&str, Vec<&str>)> {
+    let line = line.split('#').next().unwrap_or_default();
+    let mut tokens = line.split_whitespace();
+    let keyword = tokens.next()?;
+    Some((keyword, tokens.collect()))
+}
+
+fn parse_numbers(arguments: &[&str], min: usize, max: usize) -> Result<Vec<Float>, String> {
+    if arguments.len() < min || arguments.len() > max {
+        let expected = if min == max {
+            min.to_string()
+        } else {
+            format!("{} to {}", min, max)
+        };
+        return Err(format!(
+            "expected {} numbers but found {}",
+            expected,
+            arguments.len()
+        ));
+    }
+    arguments
+        .iter()
+        .map(|token| parse_number(token).map(|number| number as Float))
+        .collect()
+}
+
+// Reads three coordinates, ignoring any trailing ones such as the homogeneous `w`.
+fn parse_vector(arguments: &[&str], min: usize, max: usize) -> Result<Vector3, String> {
+    let numbers = parse_numbers(arguments, min, max)?;
+    Ok(Vector3::new(numbers[0], numbers[1], numbers[2]))
+}
+
+// A single value stands for a grey.
+fn parse_color(arguments: &[&str]) -> Result<Color, String> {
+    let numbers = parse_numbers(arguments, 1, 3)?;
+    match numbers[..] {
+        [grey] => Ok(Color::gray(grey)),
+        [r, g, b] => Ok(Color::new(r, g, b)),
+        _ => Err("expected 1 or 3 colour components".to_string()),
+    }
+}
+
+// Parses `v`, `v/vt`, `v//vn` or `v/vt/vn`, where negative indices count back from the most
+// recently defined element.
+fn parse_face_vertex(token: &str, data: &MeshData) -> Result<FaceVertex, String> {
+    let mut indices = token.split('/');
+    let position = resolve_index(indices.next(), data.positions.len(), "vertex")?;
+    let uv = match indices.next() {
+        Some("") | None => None,
+        index => Some(resolve_index(index, data.uvs.len(), "texture coordinate")?),
+    };
+    let normal = match indices.next() {
+        Some("") | None => None,
+        index => Some(resolve_index(index, data.normals.len(), "normal")?),
+    };
+    if indices.next().is_some() {
+        return Err(format!("invalid face vertex '{}'", token));
+    }
+
+    Ok(FaceVertex {
+        position,
+        uv,
+        normal,
+    })
+}
+
+fn resolve_index(token: Option<&str>, count: usize, kind: &str) -> Result<usize, String> {
+    let token = token.unwrap_or_default();
+    let index: i64 = token
+        .parse()
+        .map_err(|_| format!("invalid {} index '{}'", kind, token))?;
+    let resolved = if index < 0 {
+        count as i64 + index
+    } else {
+        index - 1
+    };
+
+    if (0..count as i64).contains(&resolved) {
+        Ok(resolved as usize)
+    } else {
+        Err(format!(
+            "{} index {} is out of range, {} defined so far",
+            kind, index, count
+        ))
+    }
+}
+
+fn group_index(data: &mut MeshData, name: &str) -> usize {
+    match data.groups.iter().position(|group| group == name) {
+        Some(index) => index,
+        None => {
+            data.groups.push(name.to_string());
+            data.groups.len() - 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hit::HitRecord;
+    use crate::ray::Ray;
+    use std::fs;
+
+    fn parse_source(source: &str) -> Result<MeshData, ModelError> {
+        parse(source, Path::new(""))
+    }
+
+    fn error(source: &str) -> String {
+        match parse_source(source) {
+            Err(error) => error.to_string(),
+            Ok(_) => panic!("'{source}' should have been rejected"),
+        }
+    }
+
+    // Identifies a material by how it responds to a ray hitting a surface head-on.
+    fn kind(material: &dyn Material) -> &'static str {
+        let normal = Normal3::new(Vector3::new(0.0, 0.0, 1.0));
+        let ray = Ray::new(Point3::new(0.0, 0.0, 1.0), Vector3::new(0.0, 0.0, -1.0));
+        let record = HitRecord::new(
+            Point3::origin(),
+            1.0,
+            (0.0, 0.0),
+            normal,
+            ray.direction,
+            material,
+        );
+
+        if !material.emitted(&record).is_black() {
+            "light"
+        } else if material.scattering_pdf(&record, normal.to_vector()) > 0.0 {
+            "lambertian"
+        } else if material.scatter(&ray, &record).unwrap().attenuated == Color::white() {
+            "dielectric"
+        } else {
+            "metal"
+        }
+    }
+
+    fn mtl_kind(source: &str) -> &'static str {
+        let mut library = HashMap::new();
+        parse_mtl(source, &mut library).unwrap();
+        kind(&*library["m"].to_material())
+    }
+
+    #[test]
+    fn resolves_positive_and_negative_indices() {
+        let data = parse_source(
+            "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvt 0 1\nvn 0 0 1\n\
+             f 1/1/1 2/2/1 3/3/1\nv 1 1 0\nf -4/-3/-1 -1/-1/-1 -2/-2/-1\nf 1//1 -3//1 -1//1\n",
+        )
+        .unwrap();
+
+        assert_eq!(data.faces.len(), 3);
+        assert_eq!(data.faces[0].positions, [0, 1, 2]);
+        assert_eq!(data.faces[1].positions, [0, 3, 2]);
+        assert_eq!(data.faces[1].uvs, Some([0, 2, 1]));
+        assert_eq!(data.faces[1].normals, Some([0, 0, 0]));
+        assert_eq!(data.faces[2].positions, [0, 1, 3]);
+        assert_eq!(data.faces[2].uvs, None);
+    }
+
+    #[test]
+    fn rejects_out_of_range_indices() {
+        assert_eq!(
+            error("v 0 0 0\nv 1 0 0\nf 1 2 -3\n"),
+            "malformed model: line 3: vertex index -3 is out of range, 2 defined so far"
+        );
+        assert_eq!(
+            error("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 0 1 2\n"),
+            "malformed model: line 4: vertex index 0 is out of range, 3 defined so far"
+        );
+        assert_eq!(
+            error("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1/1 2/1 3/1\n"),
+            "malformed model: line 4: texture coordinate index 1 is out of range, 0 defined so far"
+        );
+    }
+
+    #[test]
+    fn rejects_zero_normals() {
+        assert_eq!(
+            error("v 0 0 0\nvn 0 0 0\n"),
+            "malformed model: line 2: normal must not be zero"
+        );
+    }
+
+    #[test]
+    fn splits_polygons_into_fans_and_tracks_groups() {
+        let data = parse_source("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\ng top\nf 4 3 2\n")
+            .unwrap();
+        let positions: Vec<_> = data.faces.iter().map(|face| face.positions).collect();
+        assert_eq!(positions, [[0, 1, 2], [0, 2, 3], [3, 2, 1]]);
+        assert_eq!(data.groups, ["default", "top"]);
+        assert_eq!(data.faces[2].group, 1);
+        assert_eq!(data.materials.len(), 1);
+    }
+
+    #[test]
+    fn maps_mtl_descriptions_onto_materials() {
+        assert_eq!(
+            mtl_kind("newmtl m\nKd 0.5 0.5 0.5\nillum 2\n"),
+            "lambertian"
+        );
+        assert_eq!(mtl_kind("newmtl m\nKs 0.9\nNs 1000\nillum 3\n"), "metal");
+        assert_eq!(mtl_kind("newmtl m\nNi 1.33\nillum 7\n"), "dielectric");
+        assert_eq!(mtl_kind("newmtl m\nd 0.5\n"), "dielectric");
+        assert_eq!(mtl_kind("newmtl m\nTr 0.25\nillum 2\n"), "dielectric");
+        assert_eq!(mtl_kind("newmtl m\nKe 4 4 4\nillum 7\n"), "light");
+    }
+
+    #[test]
+    fn rejects_statements_before_newmtl() {
+        let mut library = HashMap::new();
+        assert_eq!(
+            parse_mtl("# comment\nKd 1 1 1\n", &mut library).unwrap_err(),
+            "line 2: 'Kd' before newmtl"
+        );
+    }
+
+    #[test]
+    fn loads_material_libraries_and_shares_materials() {
+        let directory = std::env::temp_dir().join(format!("raytracer-obj-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join("scene.mtl"),
+            "newmtl red\nKd 1 0 0\nnewmtl lamp\nKe 1 1 1\n",
+        )
+        .unwrap();
+
+        let result = parse(
+            "mtllib scene.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\n\
+             usemtl lamp\nf 1 2 3\nusemtl red\nf 1 2 3\nusemtl lamp\nf 1 2 3\n",
+            &directory,
+        );
+        fs::remove_dir_all(&directory).unwrap();
+
+        let data = result.unwrap();
+        let materials: Vec<_> = data.faces.iter().map(|face| face.material).collect();
+        assert_eq!(materials, [0, 1, 0]);
+        assert_eq!(kind(&*data.materials[0]), "light");
+        assert_eq!(kind(&*data.materials[1]), "lambertian");
+    }
+
+    #[test]
+    fn rejects_unknown_materials() {
+        assert_eq!(
+            error("usemtl missing\n"),
+            "malformed model: line 1: unknown material 'missing'"
+        );
+    }
+}
@@ -0,0 +1,551 @@
+use crate::color::Color;
+use crate::float::Float;
+use crate::material::Lambertian;
+use crate::mesh::{MeshData, MeshFace};
+use crate::model::{parse_number, ModelError};
+use crate::normal::Normal3;
+use crate::point::Point3;
+use crate::vector::Vector3;
+
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Clone, Copy)]
+enum ScalarType {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+enum PropertyType {
+    Scalar(ScalarType),
+    List(ScalarType, ScalarType),
+}
+
+struct Property {
+    name: String,
+    property_type: PropertyType,
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+// A property value of a single element, with lists holding their items.
+enum Value {
+    Scalar(f64),
+    List(Vec<f64>),
+}
+
+impl ScalarType {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "char" | "int8" => Ok(ScalarType::Int8),
+            "uchar" | "uint8" => Ok(ScalarType::UInt8),
+            "short" | "int16" => Ok(ScalarType::Int16),
+            "ushort" | "uint16" => Ok(ScalarType::UInt16),
+            "int" | "int32" => Ok(ScalarType::Int32),
+            "uint" | "uint32" => Ok(ScalarType::UInt32),
+            "float" | "float32" => Ok(ScalarType::Float32),
+            "double" | "float64" => Ok(ScalarType::Float64),
+            _ => Err(format!("unknown property type '{}'", name)),
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            ScalarType::Int8 | ScalarType::UInt8 => 1,
+            ScalarType::Int16 | ScalarType::UInt16 => 2,
+            ScalarType::Int32 | ScalarType::UInt32 | ScalarType::Float32 => 4,
+            ScalarType::Float64 => 8,
+        }
+    }
+}
+
+// Reads the element data that follows the header, in either encoding.
+struct BodyReader<'a> {
+    format: Format,
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl BodyReader<'_> {
+    fn read(&mut self, scalar_type: ScalarType) -> Result<f64, String> {
+        match self.format {
+            Format::Ascii => self.read_token(),
+            Format::BinaryLittleEndian => self.read_binary(scalar_type, false),
+            Format::BinaryBigEndian => self.read_binary(scalar_type, true),
+        }
+    }
+
+    fn read_token(&mut self) -> Result<f64, String> {
+        let rest = &self.bytes[self.position..];
+        let start = rest
+            .iter()
+            .position(|byte| !byte.is_ascii_whitespace())
+            .ok_or_else(|| "unexpected end of data".to_string())?;
+        let length = rest[start..]
+            .iter()
+            .position(|byte| byte.is_ascii_whitespace())
+            .unwrap_or(rest.len() - start);
+        self.position += start + length;
+
+        let token = std::str::from_utf8(&rest[start..start + length])
+            .map_err(|_| "data is not valid text".to_string())?;
+        parse_number(token)
+    }
+
+    fn read_binary(&mut self, scalar_type: ScalarType, is_big_endian: bool) -> Result<f64, String> {
+        let size = scalar_type.size();
+        let bytes = self
+            .bytes
+            .get(self.position..self.position + size)
+            .ok_or_else(|| "unexpected end of data".to_string())?;
+        self.position += size;
+
+        let mut buffer = [0; 8];
+        buffer[..size].copy_from_slice(bytes);
+        if is_big_endian {
+            buffer[..size].reverse();
+        }
+        let [b0, b1, b2, b3, ..] = buffer;
+        Ok(match scalar_type {
+            ScalarType::Int8 => b0 as i8 as f64,
+            ScalarType::UInt8 => b0 as f64,
+            ScalarType::Int16 => i16::from_le_bytes([b0, b1]) as f64,
+            ScalarType::UInt16 => u16::from_le_bytes([b0, b1]) as f64,
+            ScalarType::Int32 => i32::from_le_bytes([b0, b1, b2, b3]) as f64,
+            ScalarType::UInt32 => u32::from_le_bytes([b0, b1, b2, b3]) as f64,
+            ScalarType::Float32 => f32::from_le_bytes([b0, b1, b2, b3]) as f64,
+            ScalarType::Float64 => f64::from_le_bytes(buffer),
+        })
+    }
+}
+
+// Decodes a Stanford PLY file. Vertex positions, normals, texture coordinates and colours are
+// read; faces are triangulated as fans and all other elements are skipped.
+pub fn decode(bytes: &[u8]) -> Result<MeshData, ModelError> {
+    decode_ply(bytes).map_err(ModelError::Malformed)
+}
+
+fn decode_ply(bytes: &[u8]) -> Result<MeshData, String> {
+    let (format, elements, body_start) = parse_header(bytes)?;
+    let mut reader = BodyReader {
+        format,
+        bytes,
+        position: body_start,
+    };
+
+    let mut data = MeshData::default();
+    for element in &elements {
+        for index in 0..element.count {
+            let values = element
+                .properties
+                .iter()
+                .map(|property| read_value(&mut reader, &property.property_type))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|message| format!("{} {}: {}", element.name, index, message))?;
+            let value = |names: &[&str]| {
+                element
+                    .properties
+                    .iter()
+                    .zip(&values)
+                    .find(|(property, _)| names.contains(&property.name.as_str()))
+                    .and_then(|(_, value)| match value {
+                        Value::Scalar(number) => Some(*number as Float),
+                        Value::List(_) => None,
+                    })
+            };
+
+            match element.name.as_str() {
+                "vertex" => {
+                    let coordinates = (value(&["x"]), value(&["y"]), value(&["z"]));
+                    let (Some(x), Some(y), Some(z)) = coordinates else {
+                        return Err("vertex element lacks x, y and z properties".to_string());
+                    };
+                    data.positions.push(Point3::new(x, y, z));
+
+                    if let (Some(x), Some(y), Some(z)) =
+                        (value(&["nx"]), value(&["ny"]), value(&["nz"]))
+                    {
+                        let normal = Vector3::new(x, y, z);
+                        if normal.length_squared() == 0.0 {
+                            return Err(format!("vertex {}: normal must not be zero", index));
+                        }
+                        data.normals.push(Normal3::new(normal));
+                    }
+                    let u = value(&["u", "s", "texture_u", "texture_s"]);
+                    let v = value(&["v", "t", "texture_v", "texture_t"]);
+                    if let (Some(u), Some(v)) = (u, v) {
+                        data.uvs.push((u, v));
+                    }
+                    let channels = (value(&["red"]), value(&["green"]), value(&["blue"]));
+                    if let (Some(r), Some(g), Some(b)) = channels {
+                        data.colors.push(decode_color(element, r, g, b));
+                    }
+                }
+                "face" => {
+                    let indices = element
+                        .properties
+                        .iter()
+                        .zip(&values)
+                        .find(|(property, _)| {
+                            matches!(property.name.as_str(), "vertex_indices" | "vertex_index")
+                        })
+                        .and_then(|(_, value)| match value {
+                            Value::List(indices) => Some(indices),
+                            Value::Scalar(_) => None,
+                        })
+                        .ok_or_else(|| "face element lacks a vertex_indices list".to_string())?;
+                    add_face(&mut data, indices)
+                        .map_err(|message| format!("face {}: {}", index, message))?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Optional attributes only count when every vertex has them.
+    let vertex_count = data.positions.len();
+    let has_normals = data.normals.len() == vertex_count;
+    let has_uvs = data.uvs.len() == vertex_count;
+    if !has_normals {
+        data.normals.clear();
+    }
+    if !has_uvs {
+        data.uvs.clear();
+    }
+    if data.colors.len() != vertex_count {
+        data.colors.clear();
+    }
+    for face in &mut data.faces {
+        if has_normals {
+            face.normals = Some(face.positions);
+        }
+        if has_uvs {
+            face.uvs = Some(face.positions);
+        }
+    }
+
+    // A white surface shows the vertex colours unchanged.
+    let albedo = if data.colors.is_empty() {
+        Color::gray(0.8)
+    } else {
+        Color::white()
+    };
+    data.materials.push(Box::new(Lambertian::new(albedo)));
+    data.groups.push("default".to_string());
+    Ok(data)
+}
+
+fn parse_header(bytes: &[u8]) -> Result<(Format, Vec<Element>, usize), String> {
+    let mut position = 0;
+    let mut next_line = || -> Result<&str, String> {
+        let start = position;
+        let length = bytes[start..]
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .ok_or_else(|| "unexpected end of header".to_string())?;
+        position = start + length + 1;
+        std::str::from_utf8(&bytes[start..start + length])
+            .map(str::trim)
+            .map_err(|_| "header is not valid text".to_string())
+    };
+
+    if next_line()? != "ply" {
+        return Err("missing PLY signature".to_string());
+    }
+
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    loop {
+        let line = next_line()?;
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens[..] {
+            ["end_header"] => break,
+            ["format", name, _version] => {
+                format = Some(match name {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::BinaryLittleEndian,
+                    "binary_big_endian" => Format::BinaryBigEndian,
+                    _ => return Err(format!("unknown PLY format '{}'", name)),
+                });
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| format!("invalid element count '{}'", count))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count_type, item_type, name] => {
+                let property_type = PropertyType::List(
+                    ScalarType::parse(count_type)?,
+                    ScalarType::parse(item_type)?,
+                );
+                add_property(&mut elements, name, property_type)?;
+            }
+            ["property", scalar_type, name] => {
+                let property_type = PropertyType::Scalar(ScalarType::parse(scalar_type)?);
+                add_property(&mut elements, name, property_type)?;
+            }
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            _ => return Err(format!("invalid header line '{}'", line)),
+        }
+    }
+
+    let format = format.ok_or_else(|| "missing PLY format line".to_string())?;
+    Ok((format, elements, position))
+}
+
+fn add_property(
+    elements: &mut [Element],
+    name: &str,
+    property_type: PropertyType,
+) -> Result<(), String> {
+    let element = elements
+        .last_mut()
+        .ok_or_else(|| format!("property '{}' outside of an element", name))?;
+    element.properties.push(Property {
+        name: name.to_string(),
+        property_type,
+    });
+    Ok(())
+}
+
+fn read_value(reader: &mut BodyReader, property_type: &PropertyType) -> Result<Value, String> {
+    match *property_type {
+        PropertyType::Scalar(scalar_type) => Ok(Value::Scalar(reader.read(scalar_type)?)),
+        PropertyType::List(count_type, item_type) => {
+            let count = reader.read(count_type)?;
+            (0..count as usize)
+                .map(|_| reader.read(item_type))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::List)
+        }
+    }
+}
+
+// Integer colours span their full type range. Stored colours are gamma encoded, so the
+// gamma 2 applied on output is undone to get a linear albedo.
+fn decode_color(element: &Element, r: Float, g: Float, b: Float) -> Color {
+    let scale = element
+        .properties
+        .iter()
+        .find(|property| property.name == "red")
+        .map_or(1.0, |property| match property.property_type {
+            PropertyType::Scalar(ScalarType::UInt8) => 255.0,
+            PropertyType::Scalar(ScalarType::UInt16) => 65535.0,
+            _ => 1.0,
+        });
+    let linear = |channel: Float| (channel / scale).clamp(0.0, 1.0).powi(2);
+    Color::new(linear(r), linear(g), linear(b))
+}
+
+fn add_face(data: &mut MeshData, indices: &[f64]) -> Result<(), String> {
+    if indices.len() < 3 {
+        return Err(format!(
+            "face needs at least 3 vertices but has {}",
+            indices.len()
+        ));
+    }
+    let indices = indices
+        .iter()
+        .map(|&index| {
+            if index >= 0.0 && (index as usize) < data.positions.len() {
+                Ok(index as usize)
+            } else {
+                Err(format!("vertex index {} is out of range", index))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for i in 1..indices.len() - 1 {
+        data.faces.push(MeshFace {
+            positions: [indices[0], indices[i], indices[i + 1]],
+            normals: None,
+            uvs: None,
+            material: 0,
+            group: 0,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER_BODY: &str = "element vertex 4\n\
+        property float x\n\
+        property float y\n\
+        property float z\n\
+        property uchar red\n\
+        property uchar green\n\
+        property uchar blue\n\
+        element face 1\n\
+        property list uchar int vertex_indices\n\
+        end_header\n";
+
+    const POSITIONS: [[f32; 3]; 4] = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [1.0, 1.0, 0.5],
+        [0.0, 1.0, -2.25],
+    ];
+    const COLORS: [u8; 4] = [0, 255, 51, 255];
+
+    fn ascii_fixture() -> Vec<u8> {
+        let mut source = format!(
+            "ply\nformat ascii 1.0\ncomment a unit quad\n{}",
+            HEADER_BODY
+        );
+        for ([x, y, z], color) in POSITIONS.iter().zip(COLORS) {
+            source += &format!("{} {} {} {} {} {}\n", x, y, z, color, color, color);
+        }
+        source += "4 0 1 2 3\n";
+        source.into_bytes()
+    }
+
+    fn binary_fixture(
+        format: &str,
+        to_bytes: impl Fn(f32) -> [u8; 4],
+        index: fn(i32) -> [u8; 4],
+    ) -> Vec<u8> {
+        let mut bytes = format!("ply\nformat {} 1.0\n{}", format, HEADER_BODY).into_bytes();
+        for (position, color) in POSITIONS.iter().zip(COLORS) {
+            for component in position {
+                bytes.extend_from_slice(&to_bytes(*component));
+            }
+            bytes.extend_from_slice(&[color; 3]);
+        }
+        bytes.push(4);
+        for i in 0..4 {
+            bytes.extend_from_slice(&index(i));
+        }
+        bytes
+    }
+
+    fn assert_decodes_quad(bytes: &[u8]) {
+        let data = decode(bytes).unwrap();
+        let expected: Vec<Point3> = POSITIONS
+            .iter()
+            .map(|&[x, y, z]| Point3::new(x as Float, y as Float, z as Float))
+            .collect();
+        assert_eq!(data.positions, expected);
+
+        let faces: Vec<_> = data.faces.iter().map(|face| face.positions).collect();
+        assert_eq!(faces, [[0, 1, 2], [0, 2, 3]]);
+        assert!(data.normals.is_empty());
+        assert!(data.uvs.is_empty());
+
+        // Colours are gamma decoded: 51 / 255 = 0.2 becomes 0.04.
+        assert_eq!(data.colors[0], Color::black());
+        assert_eq!(data.colors[1], Color::white());
+        assert!((data.colors[2].r - 0.04).abs() < 1e-6);
+        assert_eq!(data.materials.len(), 1);
+        assert_eq!(data.groups, ["default"]);
+    }
+
+    fn error(bytes: &[u8]) -> String {
+        match decode(bytes) {
+            Err(error) => error.to_string(),
+            Ok(_) => panic!("PLY data should have been rejected"),
+        }
+    }
+
+    #[test]
+    fn decodes_ascii() {
+        assert_decodes_quad(&ascii_fixture());
+    }
+
+    #[test]
+    fn decodes_binary_little_endian() {
+        assert_decodes_quad(&binary_fixture(
+            "binary_little_endian",
+            f32::to_le_bytes,
+            i32::to_le_bytes,
+        ));
+    }
+
+    #[test]
+    fn decodes_binary_big_endian() {
+        assert_decodes_quad(&binary_fixture(
+            "binary_big_endian",
+            f32::to_be_bytes,
+            i32::to_be_bytes,
+        ));
+    }
+
+    #[test]
+    fn keeps_normals_and_uvs_only_when_every_vertex_has_them() {
+        let source = "ply\nformat ascii 1.0\nelement vertex 3\n\
+            property double x\nproperty double y\nproperty double z\n\
+            property double nx\nproperty double ny\nproperty double nz\n\
+            property float s\nproperty float t\n\
+            element face 1\nproperty list uchar uint vertex_index\nend_header\n\
+            0 0 0 0 0 2 0 0\n1 0 0 0 0 2 1 0\n0 1 0 0 0 2 0 1\n3 0 1 2\n";
+        let data = decode(source.as_bytes()).unwrap();
+        assert_eq!(data.normals[0], Normal3::new(Vector3::new(0.0, 0.0, 1.0)));
+        assert_eq!(data.uvs, [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]);
+        assert_eq!(data.faces[0].normals, Some([0, 1, 2]));
+        assert_eq!(data.faces[0].uvs, Some([0, 1, 2]));
+    }
+
+    #[test]
+    fn rejects_zero_normals() {
+        let source = "ply\nformat ascii 1.0\nelement vertex 2\n\
+            property float x\nproperty float y\nproperty float z\n\
+            property float nx\nproperty float ny\nproperty float nz\nend_header\n\
+            0 0 0 0 0 1\n1 0 0 0 0 0\n";
+        assert_eq!(
+            error(source.as_bytes()),
+            "malformed model: vertex 1: normal must not be zero"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_headers() {
+        assert_eq!(error(b"plx\n"), "malformed model: missing PLY signature");
+        assert_eq!(
+            error(b"ply\nelement vertex 0\nend_header\n"),
+            "malformed model: missing PLY format line"
+        );
+        assert_eq!(
+            error(b"ply\nformat ascii 1.0\nproperty float x\nend_header\n"),
+            "malformed model: property 'x' outside of an element"
+        );
+        assert_eq!(
+            error(b"ply\nformat ascii 1.0\nelement vertex 1\nproperty half x\nend_header\n"),
+            "malformed model: unknown property type 'half'"
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_and_out_of_range_data() {
+        let mut bytes = binary_fixture("binary_little_endian", f32::to_le_bytes, i32::to_le_bytes);
+        bytes.truncate(bytes.len() - 2);
+        assert_eq!(
+            error(&bytes),
+            "malformed model: face 0: unexpected end of data"
+        );
+
+        let mut bytes = ascii_fixture();
+        bytes.truncate(bytes.len() - 4);
+        bytes.extend_from_slice(b"7 3\n");
+        assert_eq!(
+            error(&bytes),
+            "malformed model: face 0: vertex index 7 is out of range"
+        );
+    }
+}
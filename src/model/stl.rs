@@ -0,0 +1,145 @@
+use crate::color::Color;
+use crate::float::Float;
+use crate::material::Lambertian;
+use crate::mesh::{MeshData, MeshFace};
+use crate::model::ModelError;
+use crate::point::Point3;
+use std::collections::HashMap;
+
+const HEADER_SIZE: usize = 80;
+const TRIANGLE_SIZE: usize = 50;
+
+// Decodes a binary STL file. Its triangles repeat every corner, so identical positions are merged
+// to give an indexed mesh; the stored facet normals are ignored in favour of the vertex order.
+pub fn decode(bytes: &[u8]) -> Result<MeshData, ModelError> {
+    let count = bytes
+        .get(HEADER_SIZE..HEADER_SIZE + 4)
+        .map(|count| u32::from_le_bytes([count[0], count[1], count[2], count[3]]) as usize)
+        .ok_or_else(|| ModelError::Malformed("unexpected end of STL header".to_string()))?;
+
+    let triangles = &bytes[HEADER_SIZE + 4..];
+    if triangles.len() != count * TRIANGLE_SIZE {
+        let message = if bytes.starts_with(b"solid") {
+            "ASCII STL files are not supported".to_string()
+        } else {
+            format!(
+                "expected {} triangles in {} bytes but found {} bytes",
+                count,
+                count * TRIANGLE_SIZE,
+                triangles.len()
+            )
+        };
+        return Err(ModelError::Malformed(message));
+    }
+
+    let mut data = MeshData::default();
+    let mut indices: HashMap<[u32; 3], usize> = HashMap::new();
+    for triangle in triangles.chunks_exact(TRIANGLE_SIZE) {
+        let mut positions = [0; 3];
+        for (corner, position) in positions.iter_mut().enumerate() {
+            // Each corner follows the 12-byte facet normal.
+            let offset = 12 + corner * 12;
+            let bits = [0, 4, 8].map(|component| {
+                let start = offset + component;
+                u32::from_le_bytes([
+                    triangle[start],
+                    triangle[start + 1],
+                    triangle[start + 2],
+                    triangle[start + 3],
+                ])
+            });
+            *position = *indices.entry(bits).or_insert_with(|| {
+                let [x, y, z] = bits.map(|bits| f32::from_bits(bits) as Float);
+                data.positions.push(Point3::new(x, y, z));
+                data.positions.len() - 1
+            });
+        }
+
+        data.faces.push(MeshFace {
+            positions,
+            normals: None,
+            uvs: None,
+            material: 0,
+            group: 0,
+        });
+    }
+
+    data.materials
+        .push(Box::new(Lambertian::new(Color::gray(0.8))));
+    data.groups.push("default".to_string());
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(triangles: &[[[f32; 3]; 3]]) -> Vec<u8> {
+        let mut bytes = vec![0; HEADER_SIZE];
+        bytes.extend_from_slice(&(triangles.len() as u32).to_le_bytes());
+        for triangle in triangles {
+            bytes.extend_from_slice(&[0; 12]);
+            for component in triangle.iter().flatten() {
+                bytes.extend_from_slice(&component.to_le_bytes());
+            }
+            bytes.extend_from_slice(&[0; 2]);
+        }
+        bytes
+    }
+
+    fn error(bytes: &[u8]) -> String {
+        match decode(bytes) {
+            Err(error) => error.to_string(),
+            Ok(_) => panic!("STL data should have been rejected"),
+        }
+    }
+
+    #[test]
+    fn merges_shared_corners() {
+        let bytes = fixture(&[
+            [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]],
+            [[0.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+        ]);
+        let data = decode(&bytes).unwrap();
+
+        assert_eq!(data.positions.len(), 4);
+        assert_eq!(data.positions[3], Point3::new(0.0, 1.0, 0.0));
+        let faces: Vec<_> = data.faces.iter().map(|face| face.positions).collect();
+        assert_eq!(faces, [[0, 1, 2], [0, 2, 3]]);
+    }
+
+    #[test]
+    fn decodes_an_empty_model() {
+        let data = decode(&fixture(&[])).unwrap();
+        assert!(data.faces.is_empty());
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        assert_eq!(
+            error(&[0; HEADER_SIZE + 2]),
+            "malformed model: unexpected end of STL header"
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_triangles() {
+        let mut bytes = fixture(&[[[0.0; 3]; 3]; 2]);
+        bytes.truncate(bytes.len() - 1);
+        assert_eq!(
+            error(&bytes),
+            "malformed model: expected 2 triangles in 100 bytes but found 99 bytes"
+        );
+    }
+
+    #[test]
+    fn rejects_ascii_files() {
+        let mut bytes = b"solid cube\n".to_vec();
+        bytes.resize(HEADER_SIZE + 4, b' ');
+        bytes.extend_from_slice(b"facet normal 0 0 1\n");
+        assert_eq!(
+            error(&bytes),
+            "malformed model: ASCII STL files are not supported"
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use crate::float::Float;
+use crate::vector::{dot, unit_vector, Vector3};
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::ops::{Index, Neg};
+
+// A unit vector perpendicular to a surface. Normals can be flipped and measured against
+// directions, but are not moved around like other vectors.
+#[derive(Clone, Copy, PartialEq)]
+#[repr(transparent)]
+pub struct Normal3(Vector3);
+
+impl Normal3 {
+    // Normalizes `vector`, which must not be zero.
+    pub fn new(vector: Vector3) -> Self {
+        Self(unit_vector(vector))
+    }
+
+    pub fn to_vector(self) -> Vector3 {
+        self.0
+    }
+
+    pub fn dot(self, direction: Vector3) -> Float {
+        dot(self.0, direction)
+    }
+
+    // Turns the normal to the side facing along `reference`.
+    pub fn face_towards(self, reference: Normal3) -> Self {
+        if dot(self.0, reference.0) < 0.0 {
+            -self
+        } else {
+            self
+        }
+    }
+}
+
+impl Display for Normal3 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Debug for Normal3 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl Index<usize> for Normal3 {
+    type Output = Float;
+    fn index(&self, axis: usize) -> &Float {
+        &self.0[axis]
+    }
+}
+
+impl Neg for Normal3 {
+    type Output = Normal3;
+    fn neg(self) -> Normal3 {
+        Normal3(-self.0)
+    }
+}
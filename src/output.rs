@@ -0,0 +1,107 @@
+use crate::color::Color;
+use crate::float::Float;
+use crate::image::Image;
+use crate::interval::Interval;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs::File;
+use std::io::{BufWriter, Error as IoError, Result as IoResult, Write};
+use std::path::Path;
+
+mod exr;
+mod hdr;
+mod pfm;
+mod png;
+mod ppm;
+mod zlib;
+
+pub use exr::{ExrCompression, ExrWriter};
+pub use hdr::HdrWriter;
+pub use pfm::PfmWriter;
+pub use png::PngWriter;
+pub use ppm::PpmWriter;
+
+pub trait ImageWriter {
+    fn write(&self, image: &Image, out: &mut dyn Write) -> IoResult<()>;
+}
+
+#[derive(Debug)]
+pub enum OutputError {
+    UnsupportedFormat(String),
+    Io(IoError),
+}
+
+impl Display for OutputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            OutputError::UnsupportedFormat(extension) => {
+                write!(
+                    f,
+                    "unsupported output format '{}', expected one of: {}",
+                    extension,
+                    FORMATS.join(", ")
+                )
+            }
+            OutputError::Io(error) => write!(f, "could not write image: {}", error),
+        }
+    }
+}
+
+impl From<IoError> for OutputError {
+    fn from(error: IoError) -> Self {
+        OutputError::Io(error)
+    }
+}
+
+pub const FORMATS: [&str; 5] = ["ppm", "png", "hdr", "pfm", "exr"];
+
+pub fn writer_for_path(path: &Path) -> Result<Box<dyn ImageWriter>, OutputError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default();
+    writer_for_format(extension)
+}
+
+pub fn writer_for_format(format: &str) -> Result<Box<dyn ImageWriter>, OutputError> {
+    let format = format.to_ascii_lowercase();
+    match format.as_str() {
+        "ppm" => Ok(Box::new(PpmWriter)),
+        "png" => Ok(Box::new(PngWriter)),
+        "hdr" => Ok(Box::new(HdrWriter)),
+        "pfm" => Ok(Box::new(PfmWriter)),
+        "exr" => Ok(Box::new(ExrWriter::new(ExrCompression::Zip))),
+        _ => Err(OutputError::UnsupportedFormat(format)),
+    }
+}
+
+pub fn write_image(
+    writer: &dyn ImageWriter,
+    image: &Image,
+    path: &Path,
+) -> Result<(), OutputError> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writer.write(image, &mut out)?;
+    out.flush()?;
+    Ok(())
+}
+
+fn linear_to_gamma(linear_component: Float) -> Float {
+    if linear_component > 0.0 {
+        linear_component.sqrt()
+    } else {
+        0.0
+    }
+}
+
+fn color_to_bytes(color: Color) -> [u8; 3] {
+    let r = linear_to_gamma(color.r);
+    let g = linear_to_gamma(color.g);
+    let b = linear_to_gamma(color.b);
+
+    let intensity = Interval::new(0.0, 0.999);
+    let r_byte: u8 = (256.0 * intensity.clamp(r)) as u8;
+    let g_byte: u8 = (256.0 * intensity.clamp(g)) as u8;
+    let b_byte: u8 = (256.0 * intensity.clamp(b)) as u8;
+
+    [r_byte, g_byte, b_byte]
+}
@@ -0,0 +1,373 @@
+use crate::color::Color;
+use crate::float::{to_f32, Float};
+use crate::image::Image;
+use crate::output::zlib::compress;
+use crate::output::ImageWriter;
+use std::io::{Result as IoResult, Write};
+
+const MAGIC: [u8; 4] = [0x76, 0x2f, 0x31, 0x01];
+const VERSION: [u8; 4] = [2, 0, 0, 0];
+const PIXEL_TYPE_FLOAT: i32 = 2;
+const LINE_ORDER_INCREASING_Y: u8 = 0;
+const ZIP_SCANLINES_PER_BLOCK: usize = 16;
+
+pub enum ExrCompression {
+    None,
+    Zip,
+}
+
+impl ExrCompression {
+    fn id(&self) -> u8 {
+        match self {
+            ExrCompression::None => 0,
+            ExrCompression::Zip => 3,
+        }
+    }
+
+    fn scanlines_per_block(&self) -> usize {
+        match self {
+            ExrCompression::None => 1,
+            ExrCompression::Zip => ZIP_SCANLINES_PER_BLOCK,
+        }
+    }
+}
+
+pub struct ExrWriter {
+    compression: ExrCompression,
+}
+
+impl ExrWriter {
+    pub fn new(compression: ExrCompression) -> Self {
+        Self { compression }
+    }
+}
+
+impl ImageWriter for ExrWriter {
+    fn write(&self, image: &Image, out: &mut dyn Write) -> IoResult<()> {
+        let header = self.header(image);
+
+        let blocks: Vec<Vec<u8>> = (0..image.height())
+            .step_by(self.compression.scanlines_per_block())
+            .map(|y| self.block(image, y))
+            .collect();
+
+        let mut offset = (header.len() + blocks.len() * 8) as u64;
+        let mut offset_table = Vec::with_capacity(blocks.len() * 8);
+        for block in &blocks {
+            offset_table.extend_from_slice(&offset.to_le_bytes());
+            offset += block.len() as u64;
+        }
+
+        out.write_all(&header)?;
+        out.write_all(&offset_table)?;
+        for block in &blocks {
+            out.write_all(block)?;
+        }
+        Ok(())
+    }
+}
+
+impl ExrWriter {
+    fn header(&self, image: &Image) -> Vec<u8> {
+        let mut header = vec![];
+        header.extend_from_slice(&MAGIC);
+        header.extend_from_slice(&VERSION);
+
+        // Channels must be listed in alphabetical order.
+        let mut channels = vec![];
+        for name in [b'B', b'G', b'R'] {
+            channels.extend_from_slice(&[name, 0]);
+            channels.extend_from_slice(&PIXEL_TYPE_FLOAT.to_le_bytes());
+            channels.extend_from_slice(&[0, 0, 0, 0]);
+            channels.extend_from_slice(&1_i32.to_le_bytes());
+            channels.extend_from_slice(&1_i32.to_le_bytes());
+        }
+        channels.push(0);
+        write_attribute(&mut header, "channels", "chlist", &channels);
+
+        write_attribute(
+            &mut header,
+            "compression",
+            "compression",
+            &[self.compression.id()],
+        );
+
+        let mut window = vec![];
+        for value in [0, 0, image.width() as i32 - 1, image.height() as i32 - 1] {
+            window.extend_from_slice(&value.to_le_bytes());
+        }
+        write_attribute(&mut header, "dataWindow", "box2i", &window);
+        write_attribute(&mut header, "displayWindow", "box2i", &window);
+
+        write_attribute(
+            &mut header,
+            "lineOrder",
+            "lineOrder",
+            &[LINE_ORDER_INCREASING_Y],
+        );
+        write_attribute(
+            &mut header,
+            "pixelAspectRatio",
+            "float",
+            &1.0_f32.to_le_bytes(),
+        );
+        write_attribute(&mut header, "screenWindowCenter", "v2f", &[0; 8]);
+        write_attribute(
+            &mut header,
+            "screenWindowWidth",
+            "float",
+            &1.0_f32.to_le_bytes(),
+        );
+
+        header.push(0);
+        header
+    }
+
+    fn block(&self, image: &Image, y_start: usize) -> Vec<u8> {
+        let y_end = (y_start + self.compression.scanlines_per_block()).min(image.height());
+
+        // Channel data is stored per scanline in the same order as the channel list.
+        let channels: [fn(Color) -> Float; 3] = [|c| c.b, |c| c.g, |c| c.r];
+
+        let mut data = Vec::with_capacity((y_end - y_start) * image.width() * 12);
+        for y in y_start..y_end {
+            for channel in channels {
+                for x in 0..image.width() {
+                    data.extend_from_slice(&to_f32(channel(image.pixel(x, y))).to_le_bytes());
+                }
+            }
+        }
+
+        let data = match self.compression {
+            ExrCompression::None => data,
+            ExrCompression::Zip => {
+                let compressed = compress(&predict(&interleave(&data)));
+                if compressed.len() < data.len() {
+                    compressed
+                } else {
+                    data
+                }
+            }
+        };
+
+        let mut block = Vec::with_capacity(data.len() + 8);
+        block.extend_from_slice(&(y_start as i32).to_le_bytes());
+        block.extend_from_slice(&(data.len() as i32).to_le_bytes());
+        block.extend_from_slice(&data);
+        block
+    }
+}
+
+fn write_attribute(header: &mut Vec<u8>, name: &str, attribute_type: &str, value: &[u8]) {
+    header.extend_from_slice(name.as_bytes());
+    header.push(0);
+    header.extend_from_slice(attribute_type.as_bytes());
+    header.push(0);
+    header.extend_from_slice(&(value.len() as i32).to_le_bytes());
+    header.extend_from_slice(value);
+}
+
+// Splits the even and odd bytes into two halves, as the ZIP codec expects.
+fn interleave(data: &[u8]) -> Vec<u8> {
+    data.iter()
+        .step_by(2)
+        .chain(data.iter().skip(1).step_by(2))
+        .copied()
+        .collect()
+}
+
+// Replaces every byte with its difference from the previous one, biased by 128.
+fn predict(data: &[u8]) -> Vec<u8> {
+    let mut previous = None;
+    data.iter()
+        .map(|&byte| {
+            let predicted = match previous {
+                Some(previous) => byte.wrapping_sub(previous).wrapping_add(128),
+                None => byte,
+            };
+            previous = Some(byte);
+            predicted
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::zlib::tests::inflate;
+
+    // Tall enough that ZIP compression needs a full and a partial block.
+    fn test_image() -> Image {
+        let mut image = Image::new(3, 20);
+        for y in 0..image.height() {
+            for x in 0..image.width() {
+                image.set_pixel(x, y, Color::new(x as Float, y as Float * 0.5, 0.25));
+            }
+        }
+        image
+    }
+
+    fn write(compression: ExrCompression, image: &Image) -> Vec<u8> {
+        let mut bytes = vec![];
+        ExrWriter::new(compression)
+            .write(image, &mut bytes)
+            .unwrap();
+        bytes
+    }
+
+    fn i32_at(bytes: &[u8], position: usize) -> i32 {
+        i32::from_le_bytes(bytes[position..position + 4].try_into().unwrap())
+    }
+
+    fn string_at(bytes: &[u8], position: &mut usize) -> String {
+        let length = bytes[*position..]
+            .iter()
+            .position(|&byte| byte == 0)
+            .unwrap();
+        let string = String::from_utf8(bytes[*position..*position + length].to_vec()).unwrap();
+        *position += length + 1;
+        string
+    }
+
+    // Returns the name, type and value of every header attribute, followed by the position of
+    // the offset table.
+    fn attributes(bytes: &[u8]) -> (Vec<(String, String, Vec<u8>)>, usize) {
+        let mut position = 8;
+        let mut attributes = vec![];
+        loop {
+            let name = string_at(bytes, &mut position);
+            if name.is_empty() {
+                return (attributes, position);
+            }
+            let attribute_type = string_at(bytes, &mut position);
+            let size = i32_at(bytes, position) as usize;
+            let value = bytes[position + 4..position + 4 + size].to_vec();
+            attributes.push((name, attribute_type, value));
+            position += 4 + size;
+        }
+    }
+
+    fn unpredict(data: &[u8]) -> Vec<u8> {
+        let mut previous = None;
+        data.iter()
+            .map(|&byte| {
+                let original = match previous {
+                    Some(previous) => byte.wrapping_add(previous).wrapping_sub(128),
+                    None => byte,
+                };
+                previous = Some(original);
+                original
+            })
+            .collect()
+    }
+
+    fn deinterleave(data: &[u8]) -> Vec<u8> {
+        let (even, odd) = data.split_at(data.len().div_ceil(2));
+        (0..data.len())
+            .map(|i| if i % 2 == 0 { even[i / 2] } else { odd[i / 2] })
+            .collect()
+    }
+
+    // Reads the pixels back, returning them with the number of blocks that were compressed.
+    fn decode(
+        bytes: &[u8],
+        width: usize,
+        height: usize,
+        lines_per_block: usize,
+    ) -> (Vec<Color>, usize) {
+        let (_, offset_table) = attributes(bytes);
+        let mut pixels = vec![Color::black(); width * height];
+        let mut compressed_blocks = 0;
+        for block in 0..height.div_ceil(lines_per_block) {
+            let entry = offset_table + block * 8;
+            let offset = u64::from_le_bytes(bytes[entry..entry + 8].try_into().unwrap()) as usize;
+            let y_start = i32_at(bytes, offset) as usize;
+            assert_eq!(y_start, block * lines_per_block);
+            let size = i32_at(bytes, offset + 4) as usize;
+            let data = &bytes[offset + 8..offset + 8 + size];
+
+            // Blocks that do not get smaller are stored as they are.
+            let raw_size = lines_per_block.min(height - y_start) * width * 12;
+            let data = if size == raw_size {
+                data.to_vec()
+            } else {
+                compressed_blocks += 1;
+                deinterleave(&unpredict(&inflate(data)))
+            };
+            assert_eq!(data.len(), raw_size);
+
+            for (i, value) in data.chunks_exact(4).enumerate() {
+                let value = f32::from_le_bytes(value.try_into().unwrap()) as Float;
+                let (line, channel, x) = (i / (width * 3), i / width % 3, i % width);
+                let pixel = &mut pixels[(y_start + line) * width + x];
+                match channel {
+                    0 => pixel.b = value,
+                    1 => pixel.g = value,
+                    _ => pixel.r = value,
+                }
+            }
+        }
+        (pixels, compressed_blocks)
+    }
+
+    #[test]
+    fn writes_required_header_attributes() {
+        let bytes = write(ExrCompression::Zip, &test_image());
+        assert_eq!(bytes[..4], MAGIC);
+        assert_eq!(bytes[4..8], VERSION);
+
+        let (attributes, _) = attributes(&bytes);
+        let names: Vec<_> = attributes
+            .iter()
+            .map(|(name, attribute_type, _)| (name.as_str(), attribute_type.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("channels", "chlist"),
+                ("compression", "compression"),
+                ("dataWindow", "box2i"),
+                ("displayWindow", "box2i"),
+                ("lineOrder", "lineOrder"),
+                ("pixelAspectRatio", "float"),
+                ("screenWindowCenter", "v2f"),
+                ("screenWindowWidth", "float"),
+            ]
+        );
+
+        let channels = &attributes[0].2;
+        assert_eq!(channels.len(), 3 * 18 + 1);
+        for (i, name) in [b'B', b'G', b'R'].into_iter().enumerate() {
+            let channel = &channels[i * 18..(i + 1) * 18];
+            assert_eq!(channel[..2], [name, 0]);
+            assert_eq!(i32_at(channel, 2), PIXEL_TYPE_FLOAT);
+        }
+        assert_eq!(attributes[1].2, [3]);
+        let window: Vec<_> = (0..4).map(|i| i32_at(&attributes[2].2, i * 4)).collect();
+        assert_eq!(window, [0, 0, 2, 19]);
+    }
+
+    #[test]
+    fn round_trips_uncompressed_scanlines() {
+        let image = test_image();
+        let bytes = write(ExrCompression::None, &image);
+        let (pixels, compressed_blocks) = decode(&bytes, 3, 20, 1);
+        assert_eq!(pixels, image.pixels());
+        assert_eq!(compressed_blocks, 0);
+    }
+
+    #[test]
+    fn round_trips_zip_blocks() {
+        let image = test_image();
+        let bytes = write(ExrCompression::Zip, &image);
+        let (pixels, compressed_blocks) = decode(&bytes, 3, 20, ZIP_SCANLINES_PER_BLOCK);
+        assert_eq!(pixels, image.pixels());
+        assert_eq!(compressed_blocks, 2);
+    }
+
+    #[test]
+    fn predicts_interleaved_bytes() {
+        assert_eq!(interleave(&[1, 2, 3, 4, 5]), [1, 3, 5, 2, 4]);
+        assert_eq!(predict(&[10, 12, 11, 11]), [10, 130, 127, 128]);
+    }
+}
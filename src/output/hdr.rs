@@ -0,0 +1,122 @@
+use crate::color::Color;
+use crate::float::Float;
+use crate::image::Image;
+use crate::output::ImageWriter;
+use std::io::{Result as IoResult, Write};
+
+pub struct HdrWriter;
+
+impl ImageWriter for HdrWriter {
+    fn write(&self, image: &Image, out: &mut dyn Write) -> IoResult<()> {
+        write!(
+            out,
+            "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+            image.height(),
+            image.width()
+        )?;
+
+        let bytes: Vec<u8> = image
+            .pixels()
+            .iter()
+            .copied()
+            .flat_map(color_to_rgbe)
+            .collect();
+        out.write_all(&bytes)
+    }
+}
+
+fn color_to_rgbe(color: Color) -> [u8; 4] {
+    let r = color.r.max(0.0);
+    let g = color.g.max(0.0);
+    let b = color.b.max(0.0);
+
+    let brightest = r.max(g).max(b);
+    if brightest < 1e-32 {
+        return [0, 0, 0, 0];
+    }
+
+    // Shared exponent such that brightest = mantissa * 2^exponent with mantissa in [0.5, 1).
+    let mut exponent = brightest.log2().floor() as i32 + 1;
+    if brightest / Float::powi(2.0, exponent) >= 1.0 {
+        exponent += 1;
+    }
+    let scale = 256.0 / Float::powi(2.0, exponent);
+
+    [
+        (r * scale) as u8,
+        (g * scale) as u8,
+        (b * scale) as u8,
+        (exponent + 128).clamp(0, 255) as u8,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgbe_to_color(rgbe: [u8; 4]) -> Color {
+        let scale = Float::powi(2.0, rgbe[3] as i32 - (128 + 8));
+        Color::new(
+            (rgbe[0] as Float + 0.5) * scale,
+            (rgbe[1] as Float + 0.5) * scale,
+            (rgbe[2] as Float + 0.5) * scale,
+        )
+    }
+
+    #[test]
+    fn encodes_known_values() {
+        assert_eq!(
+            color_to_rgbe(Color::new(1.0, 1.0, 1.0)),
+            [128, 128, 128, 129]
+        );
+        assert_eq!(color_to_rgbe(Color::new(0.5, 0.25, 0.0)), [128, 64, 0, 128]);
+        assert_eq!(color_to_rgbe(Color::new(3.0, 0.0, 0.0)), [192, 0, 0, 130]);
+    }
+
+    #[test]
+    fn encodes_black_and_negative_components_as_zero() {
+        assert_eq!(color_to_rgbe(Color::black()), [0, 0, 0, 0]);
+        assert_eq!(color_to_rgbe(Color::new(-1.0, -2.0, 0.0)), [0, 0, 0, 0]);
+        assert_eq!(color_to_rgbe(Color::new(-1.0, 1.0, 0.0))[0], 0);
+    }
+
+    #[test]
+    fn mantissa_of_brightest_component_stays_in_range() {
+        for brightest in [0.001, 0.49, 0.5, 0.999_999, 1.0, 7.9, 8.0, 1000.0] {
+            let [r, _, _, _] = color_to_rgbe(Color::new(brightest, 0.0, 0.0));
+            assert!((128..=255).contains(&r), "{brightest} encodes as {r}");
+        }
+    }
+
+    #[test]
+    fn round_trips_within_mantissa_precision() {
+        for color in [
+            Color::new(0.2, 0.4, 0.8),
+            Color::new(12.5, 3.0, 0.01),
+            Color::new(0.0001, 0.0002, 0.0003),
+        ] {
+            let decoded = rgbe_to_color(color_to_rgbe(color));
+            let brightest = color.r.max(color.g).max(color.b);
+            for (original, decoded) in [
+                (color.r, decoded.r),
+                (color.g, decoded.g),
+                (color.b, decoded.b),
+            ] {
+                assert!((original - decoded).abs() <= brightest / 128.0);
+            }
+        }
+    }
+
+    #[test]
+    fn writes_header_and_pixels_top_to_bottom() {
+        let mut image = Image::new(1, 2);
+        image.set_pixel(0, 0, Color::new(1.0, 1.0, 1.0));
+
+        let mut bytes = vec![];
+        HdrWriter.write(&image, &mut bytes).unwrap();
+
+        let header = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 2 +X 1\n";
+        assert_eq!(bytes[..header.len()], header[..]);
+        assert_eq!(bytes[header.len()..], [128, 128, 128, 129, 0, 0, 0, 0]);
+    }
+}
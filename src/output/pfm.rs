@@ -0,0 +1,63 @@
+use crate::float::to_f32;
+use crate::image::Image;
+use crate::output::ImageWriter;
+use std::io::{Result as IoResult, Write};
+
+pub struct PfmWriter;
+
+impl ImageWriter for PfmWriter {
+    fn write(&self, image: &Image, out: &mut dyn Write) -> IoResult<()> {
+        // A negative scale marks the samples as little-endian.
+        write!(out, "PF\n{} {}\n-1.0\n", image.width(), image.height())?;
+
+        // Scanlines are stored bottom-to-top.
+        let mut bytes = Vec::with_capacity(image.pixels().len() * 12);
+        for y in (0..image.height()).rev() {
+            for x in 0..image.width() {
+                let color = image.pixel(x, y);
+                for component in [color.r, color.g, color.b] {
+                    bytes.extend_from_slice(&to_f32(component).to_le_bytes());
+                }
+            }
+        }
+        out.write_all(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    #[test]
+    fn writes_little_endian_header() {
+        let mut bytes = vec![];
+        PfmWriter.write(&Image::new(3, 2), &mut bytes).unwrap();
+
+        let header = b"PF\n3 2\n-1.0\n";
+        assert_eq!(bytes[..header.len()], header[..]);
+        assert_eq!(bytes.len(), header.len() + 3 * 2 * 12);
+    }
+
+    #[test]
+    fn writes_rows_bottom_to_top() {
+        let mut image = Image::new(2, 2);
+        image.set_pixel(0, 0, Color::new(1.0, 2.0, 3.0));
+        image.set_pixel(1, 1, Color::new(4.0, 5.0, 6.0));
+
+        let mut bytes = vec![];
+        PfmWriter.write(&image, &mut bytes).unwrap();
+
+        let samples: Vec<f32> = bytes[b"PF\n2 2\n-1.0\n".len()..]
+            .chunks_exact(4)
+            .map(|sample| f32::from_le_bytes(sample.try_into().unwrap()))
+            .collect();
+        assert_eq!(
+            samples,
+            [
+                0.0, 0.0, 0.0, 4.0, 5.0, 6.0, //
+                1.0, 2.0, 3.0, 0.0, 0.0, 0.0,
+            ]
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use crate::image::Image;
+use crate::output::zlib::compress;
+use crate::output::{color_to_bytes, ImageWriter};
+use std::io::{Result as IoResult, Write};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+const BIT_DEPTH: u8 = 8;
+const COLOR_TYPE_RGB: u8 = 2;
+const FILTER_SUB: u8 = 1;
+const BYTES_PER_PIXEL: usize = 3;
+
+pub struct PngWriter;
+
+impl ImageWriter for PngWriter {
+    fn write(&self, image: &Image, out: &mut dyn Write) -> IoResult<()> {
+        out.write_all(&SIGNATURE)?;
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(image.width() as u32).to_be_bytes());
+        header.extend_from_slice(&(image.height() as u32).to_be_bytes());
+        header.extend_from_slice(&[BIT_DEPTH, COLOR_TYPE_RGB, 0, 0, 0]);
+        write_chunk(out, b"IHDR", &header)?;
+
+        write_chunk(out, b"IDAT", &compress(&filtered_scanlines(image)))?;
+        write_chunk(out, b"IEND", &[])
+    }
+}
+
+fn filtered_scanlines(image: &Image) -> Vec<u8> {
+    let row_length = image.width() * BYTES_PER_PIXEL;
+    let mut data = Vec::with_capacity(image.height() * (row_length + 1));
+
+    for row in image.pixels().chunks(image.width().max(1)) {
+        let bytes: Vec<u8> = row.iter().copied().flat_map(color_to_bytes).collect();
+        data.push(FILTER_SUB);
+        for (i, byte) in bytes.iter().enumerate() {
+            let left = if i >= BYTES_PER_PIXEL {
+                bytes[i - BYTES_PER_PIXEL]
+            } else {
+                0
+            };
+            data.push(byte.wrapping_sub(left));
+        }
+    }
+
+    data
+}
+
+fn write_chunk(out: &mut dyn Write, chunk_type: &[u8; 4], data: &[u8]) -> IoResult<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(chunk_type)?;
+    out.write_all(data)?;
+
+    let crc = crc32(chunk_type.iter().chain(data));
+    out.write_all(&crc.to_be_bytes())
+}
+
+fn crc32<'a>(bytes: impl Iterator<Item = &'a u8>) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::output::zlib::tests::inflate;
+
+    fn chunks(bytes: &[u8]) -> Vec<([u8; 4], &[u8])> {
+        let mut chunks = vec![];
+        let mut rest = bytes;
+        while !rest.is_empty() {
+            let length = u32::from_be_bytes(rest[0..4].try_into().unwrap()) as usize;
+            let chunk_type: [u8; 4] = rest[4..8].try_into().unwrap();
+            let data = &rest[8..8 + length];
+            let crc = u32::from_be_bytes(rest[8 + length..12 + length].try_into().unwrap());
+            assert_eq!(crc, crc32(rest[4..8 + length].iter()));
+            chunks.push((chunk_type, data));
+            rest = &rest[12 + length..];
+        }
+        chunks
+    }
+
+    #[test]
+    fn crc32_matches_reference_values() {
+        assert_eq!(crc32(b"123456789".iter()), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND".iter()), 0xae42_6082);
+    }
+
+    #[test]
+    fn writes_signature_and_chunks_with_valid_crcs() {
+        let mut image = Image::new(2, 1);
+        image.set_pixel(0, 0, Color::new(1.0, 0.0, 0.0));
+        image.set_pixel(1, 0, Color::new(1.0, 1.0, 0.0));
+
+        let mut bytes = vec![];
+        PngWriter.write(&image, &mut bytes).unwrap();
+        assert_eq!(bytes[..8], SIGNATURE);
+
+        let chunks = chunks(&bytes[8..]);
+        let types: Vec<&[u8; 4]> = chunks.iter().map(|(chunk_type, _)| chunk_type).collect();
+        assert_eq!(types, [b"IHDR", b"IDAT", b"IEND"]);
+        assert_eq!(chunks[0].1, [0, 0, 0, 2, 0, 0, 0, 1, 8, 2, 0, 0, 0]);
+        assert!(chunks[2].1.is_empty());
+    }
+
+    #[test]
+    fn scanlines_use_the_sub_filter() {
+        let mut image = Image::new(2, 2);
+        image.set_pixel(0, 0, Color::new(1.0, 0.0, 0.0));
+        image.set_pixel(1, 0, Color::new(1.0, 1.0, 0.0));
+        image.set_pixel(1, 1, Color::new(0.0, 0.0, 1.0));
+
+        let mut bytes = vec![];
+        PngWriter.write(&image, &mut bytes).unwrap();
+        let idat = chunks(&bytes[8..])[1].1;
+
+        assert_eq!(
+            inflate(idat),
+            [
+                FILTER_SUB, 255, 0, 0, 0, 255, 0, //
+                FILTER_SUB, 0, 0, 0, 0, 0, 255,
+            ]
+        );
+    }
+}
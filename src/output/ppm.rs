@@ -0,0 +1,19 @@
+use crate::image::Image;
+use crate::output::{color_to_bytes, ImageWriter};
+use std::io::{Result as IoResult, Write};
+
+pub struct PpmWriter;
+
+impl ImageWriter for PpmWriter {
+    fn write(&self, image: &Image, out: &mut dyn Write) -> IoResult<()> {
+        write!(out, "P6\n{} {}\n255\n", image.width(), image.height())?;
+
+        let bytes: Vec<u8> = image
+            .pixels()
+            .iter()
+            .copied()
+            .flat_map(color_to_bytes)
+            .collect();
+        out.write_all(&bytes)
+    }
+}
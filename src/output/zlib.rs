@@ -0,0 +1,367 @@
+// A small zlib (RFC 1950) encoder producing a single fixed-Huffman DEFLATE (RFC 1951) block
+// with greedy LZ77 matching. It favours simplicity over compression ratio.
+
+const WINDOW_SIZE: usize = 32 * 1024;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const MAX_CHAIN: usize = 64;
+const HASH_BITS: usize = 15;
+const NO_POSITION: usize = usize::MAX;
+
+const LENGTH_BASE: [usize; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u32; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [usize; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u32; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+    // CMF: deflate with a 32K window; FLG: no dictionary, fastest level, FCHECK bits.
+    writer.bytes.extend_from_slice(&[0x78, 0x01]);
+
+    writer.write_bits(1, 1);
+    writer.write_bits(1, 2);
+    deflate_fixed(data, &mut writer);
+    write_literal_length_code(&mut writer, 256);
+    writer.flush();
+
+    writer.bytes.extend_from_slice(&adler32(data).to_be_bytes());
+    writer.bytes
+}
+
+fn deflate_fixed(data: &[u8], writer: &mut BitWriter) {
+    let mut head = vec![NO_POSITION; 1 << HASH_BITS];
+    let mut previous = vec![NO_POSITION; data.len()];
+
+    let mut position = 0;
+    while position < data.len() {
+        let (length, distance) = longest_match(data, position, &head, &previous);
+
+        let step = if length >= MIN_MATCH {
+            write_match(writer, length, distance);
+            length
+        } else {
+            write_literal_length_code(writer, data[position] as usize);
+            1
+        };
+
+        for _ in 0..step {
+            if position + MIN_MATCH <= data.len() {
+                let hash = hash(&data[position..]);
+                previous[position] = head[hash];
+                head[hash] = position;
+            }
+            position += 1;
+        }
+    }
+}
+
+fn longest_match(
+    data: &[u8],
+    position: usize,
+    head: &[usize],
+    previous: &[usize],
+) -> (usize, usize) {
+    if position + MIN_MATCH > data.len() {
+        return (0, 0);
+    }
+
+    let max_length = MAX_MATCH.min(data.len() - position);
+    let mut best = (0, 0);
+    let mut candidate = head[hash(&data[position..])];
+    let mut chain = 0;
+
+    while candidate != NO_POSITION && position - candidate <= WINDOW_SIZE && chain < MAX_CHAIN {
+        let length = data[candidate..]
+            .iter()
+            .zip(&data[position..position + max_length])
+            .take_while(|(a, b)| a == b)
+            .count();
+        if length > best.0 {
+            best = (length, position - candidate);
+            if length == max_length {
+                break;
+            }
+        }
+        candidate = previous[candidate];
+        chain += 1;
+    }
+
+    best
+}
+
+fn hash(bytes: &[u8]) -> usize {
+    let value = (bytes[0] as usize) << 16 | (bytes[1] as usize) << 8 | bytes[2] as usize;
+    (value.wrapping_mul(2_654_435_761) >> 8) & ((1 << HASH_BITS) - 1)
+}
+
+fn write_match(writer: &mut BitWriter, length: usize, distance: usize) {
+    let length_index = LENGTH_BASE
+        .iter()
+        .rposition(|&base| base <= length)
+        .unwrap_or(0);
+    write_literal_length_code(writer, 257 + length_index);
+    writer.write_bits(
+        (length - LENGTH_BASE[length_index]) as u32,
+        LENGTH_EXTRA_BITS[length_index],
+    );
+
+    let distance_index = DISTANCE_BASE
+        .iter()
+        .rposition(|&base| base <= distance)
+        .unwrap_or(0);
+    writer.write_reversed_bits(distance_index as u32, 5);
+    writer.write_bits(
+        (distance - DISTANCE_BASE[distance_index]) as u32,
+        DISTANCE_EXTRA_BITS[distance_index],
+    );
+}
+
+fn write_literal_length_code(writer: &mut BitWriter, symbol: usize) {
+    let symbol = symbol as u32;
+    match symbol {
+        0..=143 => writer.write_reversed_bits(0x30 + symbol, 8),
+        144..=255 => writer.write_reversed_bits(0x190 + symbol - 144, 9),
+        256..=279 => writer.write_reversed_bits(symbol - 256, 7),
+        _ => writer.write_reversed_bits(0xc0 + symbol - 280, 8),
+    }
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MODULUS: u32 = 65521;
+    let mut a = 1_u32;
+    let mut b = 0_u32;
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MODULUS;
+        b %= MODULUS;
+    }
+    (b << 16) | a
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bit_count: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: vec![],
+            buffer: 0,
+            bit_count: 0,
+        }
+    }
+
+    fn write_bits(&mut self, value: u32, count: u32) {
+        for i in 0..count {
+            self.buffer |= ((value >> i) & 1) << self.bit_count;
+            self.bit_count += 1;
+            if self.bit_count == 8 {
+                self.bytes.push(self.buffer as u8);
+                self.buffer = 0;
+                self.bit_count = 0;
+            }
+        }
+    }
+
+    // Huffman codes are packed starting from their most significant bit.
+    fn write_reversed_bits(&mut self, code: u32, count: u32) {
+        let reversed = code.reverse_bits() >> (32 - count);
+        self.write_bits(reversed, count);
+    }
+
+    fn flush(&mut self) {
+        if self.bit_count > 0 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer = 0;
+            self.bit_count = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    // A minimal zlib decoder for stored and fixed-Huffman blocks, enough to check the encoder.
+    pub(crate) fn inflate(stream: &[u8]) -> Vec<u8> {
+        assert_eq!(stream[0] & 0x0f, 8, "compression method must be deflate");
+        assert_eq!(
+            u16::from_be_bytes([stream[0], stream[1]]) % 31,
+            0,
+            "bad FCHECK"
+        );
+
+        let mut reader = BitReader::new(&stream[2..]);
+        let mut output = vec![];
+        loop {
+            let last = reader.bits(1) == 1;
+            match reader.bits(2) {
+                0 => {
+                    reader.align();
+                    let length = reader.bits(16) as usize;
+                    let complement = reader.bits(16) as usize;
+                    assert_eq!(length, !complement & 0xffff);
+                    for _ in 0..length {
+                        output.push(reader.bits(8) as u8);
+                    }
+                }
+                1 => inflate_fixed(&mut reader, &mut output),
+                block_type => panic!("unsupported block type {block_type}"),
+            }
+            if last {
+                break;
+            }
+        }
+
+        reader.align();
+        let trailer = reader.position / 8;
+        let checksum = u32::from_be_bytes(reader.bytes[trailer..trailer + 4].try_into().unwrap());
+        assert_eq!(checksum, adler32(&output));
+        output
+    }
+
+    fn inflate_fixed(reader: &mut BitReader, output: &mut Vec<u8>) {
+        loop {
+            let symbol = read_literal_length_code(reader);
+            match symbol {
+                0..=255 => output.push(symbol as u8),
+                256 => return,
+                _ => {
+                    let index = symbol - 257;
+                    let length =
+                        LENGTH_BASE[index] + reader.bits(LENGTH_EXTRA_BITS[index]) as usize;
+                    let index = reader.reversed_bits(5) as usize;
+                    let distance =
+                        DISTANCE_BASE[index] + reader.bits(DISTANCE_EXTRA_BITS[index]) as usize;
+                    assert!(
+                        distance <= output.len(),
+                        "distance reaches before the start"
+                    );
+                    for _ in 0..length {
+                        output.push(output[output.len() - distance]);
+                    }
+                }
+            }
+        }
+    }
+
+    fn read_literal_length_code(reader: &mut BitReader) -> usize {
+        let mut code = reader.reversed_bits(7);
+        if code <= 0x17 {
+            return code as usize + 256;
+        }
+        code = code << 1 | reader.bits(1);
+        match code {
+            0x30..=0xbf => (code - 0x30) as usize,
+            0xc0..=0xc7 => (code - 0xc0) as usize + 280,
+            _ => ((code << 1 | reader.bits(1)) - 0x190) as usize + 144,
+        }
+    }
+
+    struct BitReader<'a> {
+        bytes: &'a [u8],
+        position: usize,
+    }
+
+    impl<'a> BitReader<'a> {
+        fn new(bytes: &'a [u8]) -> Self {
+            Self { bytes, position: 0 }
+        }
+
+        fn bits(&mut self, count: u32) -> u32 {
+            let mut value = 0;
+            for i in 0..count {
+                let bit = (self.bytes[self.position / 8] >> (self.position % 8)) & 1;
+                value |= (bit as u32) << i;
+                self.position += 1;
+            }
+            value
+        }
+
+        fn reversed_bits(&mut self, count: u32) -> u32 {
+            (0..count).fold(0, |code, _| code << 1 | self.bits(1))
+        }
+
+        fn align(&mut self) {
+            self.position = self.position.div_ceil(8) * 8;
+        }
+    }
+
+    fn pseudo_random_bytes(count: usize) -> Vec<u8> {
+        let mut state = 0x2545_f491_u32;
+        (0..count)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn adler32_matches_reference_values() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn round_trips_empty_input() {
+        assert_eq!(inflate(&compress(b"")), b"");
+    }
+
+    #[test]
+    fn round_trips_short_text() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        assert_eq!(inflate(&compress(data)), data);
+    }
+
+    #[test]
+    fn round_trips_repetitive_data_with_matches() {
+        let data: Vec<u8> = b"abcabcabd".iter().copied().cycle().take(10_000).collect();
+        let compressed = compress(&data);
+        assert!(compressed.len() < data.len() / 10);
+        assert_eq!(inflate(&compressed), data);
+    }
+
+    #[test]
+    fn round_trips_long_runs_and_all_byte_values() {
+        let mut data = vec![0; 1000];
+        data.extend((0..=255).cycle().take(3 * 256));
+        data.extend(vec![255; 70_000]);
+        assert_eq!(inflate(&compress(&data)), data);
+    }
+
+    #[test]
+    fn round_trips_incompressible_data() {
+        let data = pseudo_random_bytes(50_000);
+        assert_eq!(inflate(&compress(&data)), data);
+    }
+
+    #[test]
+    fn test_decoder_reads_stored_blocks() {
+        let data = b"stored";
+        let mut stream = vec![0x78, 0x01, 0x01];
+        stream.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        stream.extend_from_slice(&(!(data.len() as u16)).to_le_bytes());
+        stream.extend_from_slice(data);
+        stream.extend_from_slice(&adler32(data).to_be_bytes());
+        assert_eq!(inflate(&stream), data);
+    }
+}
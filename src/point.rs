@@ -0,0 +1,98 @@
+use crate::float::Float;
+use crate::vector::Vector3;
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::ops::{Add, AddAssign, Index, Sub, SubAssign};
+
+// A position in space. Points can be offset by vectors and subtracted from one another, but
+// adding or scaling them has no meaning.
+#[derive(Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct Point3 {
+    pub x: Float,
+    pub y: Float,
+    pub z: Float,
+}
+
+impl Point3 {
+    pub fn new(x: Float, y: Float, z: Float) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn origin() -> Self {
+        Self::new(0.0, 0.0, 0.0)
+    }
+
+    // The vector from the origin to this point.
+    pub fn to_vector(self) -> Vector3 {
+        Vector3::new(self.x, self.y, self.z)
+    }
+}
+
+impl Display for Point3 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+impl Debug for Point3 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+impl Default for Point3 {
+    fn default() -> Point3 {
+        Point3::origin()
+    }
+}
+
+impl Index<usize> for Point3 {
+    type Output = Float;
+    fn index(&self, axis: usize) -> &Float {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Point3 axis out of range: {}", axis),
+        }
+    }
+}
+
+// Arithmetic operators
+impl Add<Vector3> for Point3 {
+    type Output = Point3;
+    fn add(self, other: Vector3) -> Point3 {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub<Vector3> for Point3 {
+    type Output = Point3;
+    fn sub(self, other: Vector3) -> Point3 {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Vector3;
+    fn sub(self, other: Point3) -> Vector3 {
+        Vector3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+// Assignment operators
+impl AddAssign<Vector3> for Point3 {
+    fn add_assign(&mut self, other: Vector3) {
+        self.x += other.x;
+        self.y += other.y;
+        self.z += other.z;
+    }
+}
+
+impl SubAssign<Vector3> for Point3 {
+    fn sub_assign(&mut self, other: Vector3) {
+        self.x -= other.x;
+        self.y -= other.y;
+        self.z -= other.z;
+    }
+}
@@ -0,0 +1,252 @@
+use crate::aabb::Aabb;
+use crate::float::Float;
+use crate::hit::{HitRecord, Hittable, HittableList};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::normal::Normal3;
+use crate::point::Point3;
+use crate::ray::Ray;
+use crate::vector::{cross, dot, Vector3};
+
+// The region of the plane spanned by the two edges that belongs to the surface.
+#[derive(Clone, Copy)]
+pub enum PlanarShape {
+    Parallelogram,
+    Triangle,
+    // An ellipse centred on the origin with the edges as its semi-axes.
+    Disk,
+}
+
+// A planar primitive given by an origin and two edge vectors.
+pub struct Quad {
+    origin: Point3,
+    u: Vector3,
+    v: Vector3,
+    // Maps a point in the plane to its coordinates along the edges.
+    w: Vector3,
+    normal: Normal3,
+    d: Float,
+    shape: PlanarShape,
+    material: Box<dyn Material>,
+    bbox: Aabb,
+}
+
+// The constructors return `None` when the edges are parallel or one of them is zero, as the
+// shape would then have no area.
+impl Quad {
+    pub fn new(
+        origin: Point3,
+        u: Vector3,
+        v: Vector3,
+        material: Box<dyn Material>,
+    ) -> Option<Self> {
+        Self::with_shape(origin, u, v, PlanarShape::Parallelogram, material)
+    }
+
+    pub fn triangle(
+        origin: Point3,
+        u: Vector3,
+        v: Vector3,
+        material: Box<dyn Material>,
+    ) -> Option<Self> {
+        Self::with_shape(origin, u, v, PlanarShape::Triangle, material)
+    }
+
+    pub fn disk(
+        center: Point3,
+        u: Vector3,
+        v: Vector3,
+        material: Box<dyn Material>,
+    ) -> Option<Self> {
+        Self::with_shape(center, u, v, PlanarShape::Disk, material)
+    }
+
+    pub fn with_shape(
+        origin: Point3,
+        u: Vector3,
+        v: Vector3,
+        shape: PlanarShape,
+        material: Box<dyn Material>,
+    ) -> Option<Self> {
+        let n = cross(u, v);
+        // The cross product is compared relative to the edge lengths, so the test does not
+        // depend on the size of the shape.
+        if n.length_squared()
+            <= Float::EPSILON * Float::EPSILON * u.length_squared() * v.length_squared()
+        {
+            return None;
+        }
+        let normal = Normal3::new(n);
+        let d = normal.dot(origin.to_vector());
+        let w = n / dot(n, n);
+
+        let corners = match shape {
+            PlanarShape::Parallelogram => [origin, origin + u, origin + v, origin + u + v],
+            PlanarShape::Triangle => [origin, origin + u, origin + v, origin],
+            PlanarShape::Disk => [
+                origin - u - v,
+                origin + u - v,
+                origin - u + v,
+                origin + u + v,
+            ],
+        };
+        let bbox = Aabb::surrounding(
+            &Aabb::from_points(corners[0], corners[3]),
+            &Aabb::from_points(corners[1], corners[2]),
+        );
+
+        Some(Self {
+            origin,
+            u,
+            v,
+            w,
+            normal,
+            d,
+            shape,
+            material,
+            bbox,
+        })
+    }
+
+    // Returns the texture coordinates of a point given in edge coordinates, or `None` when it
+    // lies outside the shape.
+    fn interior_uv(&self, alpha: Float, beta: Float) -> Option<(Float, Float)> {
+        let unit = Interval::new(0.0, 1.0);
+        match self.shape {
+            PlanarShape::Parallelogram => {
+                (unit.contains(alpha) && unit.contains(beta)).then_some((alpha, beta))
+            }
+            PlanarShape::Triangle => {
+                (alpha >= 0.0 && beta >= 0.0 && alpha + beta <= 1.0).then_some((alpha, beta))
+            }
+            PlanarShape::Disk => (alpha * alpha + beta * beta <= 1.0)
+                .then_some(((alpha + 1.0) / 2.0, (beta + 1.0) / 2.0)),
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        let denominator = self.normal.dot(ray.direction);
+        // Rays parallel to the plane never hit it. The normal has unit length, so the
+        // denominator is compared relative to the length of the direction.
+        if denominator * denominator
+            <= Float::EPSILON * Float::EPSILON * ray.direction.length_squared()
+        {
+            return None;
+        }
+
+        let t = (self.d - self.normal.dot(ray.origin.to_vector())) / denominator;
+        if !ray_t.surrounds(t) {
+            return None;
+        }
+
+        let point = ray.at(t);
+        let planar_point = point - self.origin;
+        let alpha = dot(self.w, cross(planar_point, self.v));
+        let beta = dot(self.w, cross(self.u, planar_point));
+        let uv = self.interior_uv(alpha, beta)?;
+
+        Some(HitRecord::new(
+            point,
+            t,
+            uv,
+            self.normal,
+            ray.direction,
+            &*self.material,
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox.clone()
+    }
+}
+
+// Builds the six sides of the axis-aligned box with opposite corners `a` and `b`. Each side owns
+// its material, so `material` is called once per side; it can return clones of an `Arc` to share
+// one. Returns `None` when the corners share a coordinate, as the box would then be flat.
+pub fn make_box(
+    a: Point3,
+    b: Point3,
+    mut material: impl FnMut() -> Box<dyn Material>,
+) -> Option<HittableList> {
+    if a.x == b.x || a.y == b.y || a.z == b.z {
+        return None;
+    }
+    let min = Point3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z));
+    let max = Point3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z));
+
+    let dx = Vector3::new(max.x - min.x, 0.0, 0.0);
+    let dy = Vector3::new(0.0, max.y - min.y, 0.0);
+    let dz = Vector3::new(0.0, 0.0, max.z - min.z);
+
+    let sides = [
+        // front
+        (Point3::new(min.x, min.y, max.z), dx, dy),
+        // right
+        (Point3::new(max.x, min.y, max.z), -dz, dy),
+        // back
+        (Point3::new(max.x, min.y, min.z), -dx, dy),
+        // left
+        (Point3::new(min.x, min.y, min.z), dz, dy),
+        // top
+        (Point3::new(min.x, max.y, max.z), dx, -dz),
+        // bottom
+        (Point3::new(min.x, min.y, min.z), dx, dz),
+    ];
+
+    let mut sides_list = HittableList::new();
+    for (origin, u, v) in sides {
+        sides_list.add(Box::new(Quad::new(origin, u, v, material())?));
+    }
+    Some(sides_list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::material::Lambertian;
+
+    fn material() -> Box<dyn Material> {
+        Box::new(Lambertian::new(Color::gray(0.5)))
+    }
+
+    #[test]
+    fn builds_boxes_from_any_opposite_corners() {
+        let corners = (Point3::new(1.0, 2.0, 3.0), Point3::new(-1.0, 0.0, 5.0));
+        let sides = make_box(corners.0, corners.1, material).unwrap();
+        let bbox = sides.bounding_box();
+        // The faces are padded slightly to give them some thickness.
+        for (axis, min, max) in [(bbox.x, -1.0, 1.0), (bbox.y, 0.0, 2.0), (bbox.z, 3.0, 5.0)] {
+            assert!((axis.min - min).abs() < 0.01 && (axis.max - max).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn rejects_flat_boxes() {
+        let min = Point3::new(0.0, 0.0, 0.0);
+        for max in [
+            Point3::new(0.0, 1.0, 1.0),
+            Point3::new(1.0, 0.0, 1.0),
+            Point3::new(1.0, 1.0, 0.0),
+        ] {
+            assert!(make_box(min, max, material).is_none());
+        }
+    }
+
+    #[test]
+    fn rejects_parallel_and_zero_edges() {
+        let origin = Point3::new(0.0, 0.0, 0.0);
+        let u = Vector3::new(1e-6, 0.0, 0.0);
+        for v in [
+            Vector3::new(3e6, 0.0, 0.0),
+            Vector3::new(-1.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 0.0),
+        ] {
+            assert!(Quad::new(origin, u, v, material()).is_none());
+            assert!(Quad::disk(origin, v, u, material()).is_none());
+        }
+        assert!(Quad::triangle(origin, u, Vector3::new(0.0, 1e6, 0.0), material()).is_some());
+    }
+}
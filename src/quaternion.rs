@@ -0,0 +1,247 @@
+use crate::float::Float;
+use crate::matrix::{Matrix3, Matrix4};
+use crate::vector::{unit_vector, Vector3};
+use std::ops::Mul;
+
+// A rotation stored as `w + xi + yj + zk`. Rotations use unit quaternions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quaternion {
+    pub w: Float,
+    pub x: Float,
+    pub y: Float,
+    pub z: Float,
+}
+
+impl Quaternion {
+    pub fn new(w: Float, x: Float, y: Float, z: Float) -> Self {
+        Self { w, x, y, z }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(1.0, 0.0, 0.0, 0.0)
+    }
+
+    // Matches `Matrix4::rotation` for the same axis and angle.
+    pub fn from_axis_angle(axis: Vector3, degrees: Float) -> Self {
+        let axis = unit_vector(axis);
+        let (sin, cos) = (degrees.to_radians() / 2.0).sin_cos();
+        Self::new(cos, axis.x * sin, axis.y * sin, axis.z * sin)
+    }
+
+    pub fn dot(&self, other: &Quaternion) -> Float {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn length(&self) -> Float {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalized(&self) -> Self {
+        let length = self.length();
+        Self::new(
+            self.w / length,
+            self.x / length,
+            self.y / length,
+            self.z / length,
+        )
+    }
+
+    // The inverse rotation, for unit quaternions.
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    pub fn rotate(&self, vector: Vector3) -> Vector3 {
+        let rotated =
+            &(self * &Quaternion::new(0.0, vector.x, vector.y, vector.z)) * &self.conjugate();
+        Vector3::new(rotated.x, rotated.y, rotated.z)
+    }
+
+    // Spherical linear interpolation along the shorter arc, turning at a constant rate.
+    pub fn slerp(&self, other: &Quaternion, t: Float) -> Self {
+        let mut cos_theta = self.dot(other);
+        // `q` and `-q` are the same rotation; flip one to take the shorter way round.
+        let other = if cos_theta < 0.0 {
+            cos_theta = -cos_theta;
+            Self::new(-other.w, -other.x, -other.y, -other.z)
+        } else {
+            other.clone()
+        };
+
+        // Nearly parallel rotations fall back to a linear blend to avoid dividing by zero.
+        let (a, b) = if cos_theta > 0.9995 {
+            (1.0 - t, t)
+        } else {
+            let theta = cos_theta.acos();
+            let sin_theta = theta.sin();
+            (
+                ((1.0 - t) * theta).sin() / sin_theta,
+                (t * theta).sin() / sin_theta,
+            )
+        };
+
+        Self::new(
+            a * self.w + b * other.w,
+            a * self.x + b * other.x,
+            a * self.y + b * other.y,
+            a * self.z + b * other.z,
+        )
+        .normalized()
+    }
+
+    pub fn to_matrix3(&self) -> Matrix3 {
+        let Quaternion { w, x, y, z } = self.normalized();
+        Matrix3::new([
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+            ],
+        ])
+    }
+
+    pub fn to_matrix4(&self) -> Matrix4 {
+        Matrix4::from_linear(&self.to_matrix3(), Vector3::zero())
+    }
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+// The Hamilton product, which applies `other` first and then `self`.
+impl Mul for &Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, other: &Quaternion) -> Quaternion {
+        Quaternion::new(
+            self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+            self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+        )
+    }
+}
+
+impl Mul for Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, other: Quaternion) -> Quaternion {
+        &self * &other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: Float, expected: Float) {
+        assert!(
+            (actual - expected).abs() < 1e-5,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    fn assert_vectors_close(actual: Vector3, expected: Vector3) {
+        assert_close(actual.x, expected.x);
+        assert_close(actual.y, expected.y);
+        assert_close(actual.z, expected.z);
+    }
+
+    fn assert_same_rotation(actual: &Quaternion, expected: &Quaternion) {
+        // `q` and `-q` describe the same rotation.
+        assert_close(actual.dot(expected).abs(), 1.0);
+    }
+
+    #[test]
+    fn matches_the_rotation_matrix() {
+        for (axis, degrees) in [
+            (Vector3::new(0.0, 0.0, 1.0), 90.0),
+            (Vector3::new(1.0, 2.0, -0.5), 37.0),
+            (Vector3::new(-3.0, 0.5, 1.0), 250.0),
+        ] {
+            let quaternion = Quaternion::from_axis_angle(axis, degrees);
+            let matrix = Matrix4::rotation(axis, degrees);
+            let from_quaternion = quaternion.to_matrix4();
+            for i in 0..4 {
+                for j in 0..4 {
+                    assert_close(from_quaternion.rows[i][j], matrix.rows[i][j]);
+                }
+            }
+
+            let vector = Vector3::new(0.3, -1.2, 2.0);
+            let rotated = quaternion.rotate(vector);
+            assert_vectors_close(rotated, matrix.transform_vector(vector));
+            assert_vectors_close(rotated, quaternion.to_matrix3().transform(vector));
+        }
+    }
+
+    #[test]
+    fn rotates_counter_clockwise() {
+        let quaternion = Quaternion::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), 90.0);
+        assert_vectors_close(
+            quaternion.rotate(Vector3::new(1.0, 0.0, 0.0)),
+            Vector3::new(0.0, 1.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn slerp_hits_its_endpoints() {
+        let start = Quaternion::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), 10.0);
+        let end = Quaternion::from_axis_angle(Vector3::new(1.0, 1.0, 0.0), 120.0);
+        assert_same_rotation(&start.slerp(&end, 0.0), &start);
+        assert_same_rotation(&start.slerp(&end, 1.0), &end);
+    }
+
+    #[test]
+    fn slerp_turns_at_a_constant_rate() {
+        let axis = Vector3::new(0.0, 0.0, 1.0);
+        let start = Quaternion::identity();
+        let end = Quaternion::from_axis_angle(axis, 90.0);
+        assert_same_rotation(
+            &start.slerp(&end, 0.5),
+            &Quaternion::from_axis_angle(axis, 45.0),
+        );
+        assert_same_rotation(
+            &start.slerp(&end, 0.25),
+            &Quaternion::from_axis_angle(axis, 22.5),
+        );
+    }
+
+    #[test]
+    fn slerp_takes_the_shorter_arc() {
+        let axis = Vector3::new(0.0, 0.0, 1.0);
+        let start = Quaternion::identity();
+        let end = Quaternion::from_axis_angle(axis, 90.0);
+        let negated = Quaternion::new(-end.w, -end.x, -end.y, -end.z);
+
+        let midpoint = start.slerp(&negated, 0.5);
+        assert_same_rotation(&midpoint, &Quaternion::from_axis_angle(axis, 45.0));
+        assert_vectors_close(
+            midpoint.rotate(Vector3::new(1.0, 0.0, 0.0)),
+            unit_vector(Vector3::new(1.0, 1.0, 0.0)),
+        );
+    }
+
+    #[test]
+    fn slerp_between_nearly_equal_rotations_stays_normalized() {
+        let axis = Vector3::new(1.0, 0.0, 0.0);
+        let start = Quaternion::from_axis_angle(axis, 30.0);
+        let end = Quaternion::from_axis_angle(axis, 30.01);
+        let halfway = start.slerp(&end, 0.5);
+        assert_close(halfway.length(), 1.0);
+        assert_same_rotation(&halfway, &Quaternion::from_axis_angle(axis, 30.005));
+    }
+}
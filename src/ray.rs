@@ -1,16 +1,50 @@
+use crate::float::{Float, ROUNDING_TOLERANCE};
+use crate::point::Point3;
 use crate::vector::Vector3;
 
+const MIN_T: Float = 0.001;
+
 pub struct Ray {
-    pub origin: Vector3,
+    pub origin: Point3,
     pub direction: Vector3,
 }
 
 impl Ray {
-    pub fn new(origin: Vector3, direction: Vector3) -> Self {
+    pub fn new(origin: Point3, direction: Vector3) -> Self {
         Self { origin, direction }
     }
 
-    pub fn at(&self, t: f64) -> Vector3 {
-        &self.origin + t * &self.direction
+    pub fn at(&self, t: Float) -> Point3 {
+        self.origin + t * self.direction
+    }
+
+    // The smallest `t` at which a surface counts as hit, so that rays leaving a surface do not
+    // hit it again through rounding error. Near the origin a fixed margin is enough.
+    pub fn min_t(&self) -> Float {
+        let Point3 { x, y, z } = self.origin;
+        let magnitude = x.abs().max(y.abs()).max(z.abs());
+        (ROUNDING_TOLERANCE * magnitude / self.direction.length()).max(MIN_T)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_t_is_fixed_near_the_origin() {
+        let ray = Ray::new(Point3::new(1.0, -2.0, 0.5), Vector3::new(0.0, 3.0, 0.0));
+        assert_eq!(ray.min_t(), MIN_T);
+    }
+
+    #[test]
+    fn min_t_grows_with_distance_from_the_origin() {
+        let direction = Vector3::new(0.0, 0.0, 2.0);
+        let far = Ray::new(Point3::new(0.0, -1e14, 0.0), direction);
+        let expected = ROUNDING_TOLERANCE * 1e14 / 2.0;
+        assert!((far.min_t() - expected).abs() <= 1e-3 * expected);
+
+        let farther = Ray::new(Point3::new(0.0, -1e15, 0.0), direction);
+        assert!(farther.min_t() > far.min_t());
     }
 }
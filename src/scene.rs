@@ -0,0 +1,824 @@
+use crate::camera::{CameraLens, CameraPose, CameraPoseError, RenderSettings};
+use crate::color::Color;
+use crate::environment::{Environment, EnvironmentLight, Gradient, ImageEnvironment, SolidColor};
+use crate::float::Float;
+use crate::hit::{Hittable, HittableList};
+use crate::input::read_image;
+use crate::json::{parse, Json, JsonValue};
+use crate::material::{Dielectric, DiffuseLight, Lambertian, Material, Metal};
+use crate::matrix::Matrix4;
+use crate::mesh::Mesh;
+use crate::model::load_model;
+use crate::normal::Normal3;
+use crate::point::Point3;
+use crate::quad::{make_box, PlanarShape, Quad};
+use crate::sphere::Sphere;
+use crate::transform::{Instance, Transform};
+use crate::triangle::Triangle;
+use crate::vector::Vector3;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs;
+use std::io::Error as IoError;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+// Identifies a loaded mesh by its path, selected groups and material override.
+type MeshKey = (PathBuf, Option<Vec<String>>, Option<String>);
+
+// Named materials are built once and shared by every object that uses them.
+type Materials = HashMap<String, Arc<dyn Material>>;
+
+pub struct Scene {
+    pub world: HittableList,
+    pub render: RenderSettings,
+    pub pose: CameraPose,
+    pub lens: CameraLens,
+    pub environment: Box<dyn Environment>,
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(PathBuf, IoError),
+    Invalid {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+}
+
+impl Display for SceneError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            SceneError::Io(path, error) => {
+                write!(f, "could not read '{}': {}", path.display(), error)
+            }
+            SceneError::Invalid {
+                line,
+                column,
+                message,
+            } => write!(f, "line {}, column {}: {}", line, column, message),
+        }
+    }
+}
+
+pub fn load_scene(path: &Path) -> Result<Scene, SceneError> {
+    let source =
+        fs::read_to_string(path).map_err(|error| SceneError::Io(path.to_path_buf(), error))?;
+    let base_directory = path.parent().unwrap_or(Path::new(""));
+    parse_scene(&source, base_directory)
+}
+
+// Builds a scene from its JSON description. Relative paths are resolved against `base_directory`.
+pub fn parse_scene(source: &str, base_directory: &Path) -> Result<Scene, SceneError> {
+    let root = parse(source).map_err(|error| SceneError::Invalid {
+        line: error.line,
+        column: error.column,
+        message: error.message,
+    })?;
+    let root = Field::new("scene".to_string(), &root);
+    root.expect_fields(&["camera", "render", "environment", "materials", "objects"])?;
+
+    let mut materials = Materials::new();
+    if let Some(field) = root.optional("materials") {
+        for (name, material) in field.members()? {
+            materials.insert(name.to_string(), Arc::from(build_material(&material)?));
+        }
+    }
+
+    let mut world = HittableList::new();
+    let mut meshes = HashMap::new();
+    if let Some(objects) = root.optional("objects") {
+        for object in objects.elements()? {
+            build_object(&object, &materials, &mut meshes, base_directory, &mut world)?;
+        }
+    }
+
+    let (pose, lens) = build_camera(&root)?;
+    let render = build_render_settings(&root)?;
+    let environment = match root.optional("environment") {
+        Some(environment) => build_environment(&environment, base_directory)?,
+        None => Box::new(Gradient::default()),
+    };
+
+    Ok(Scene {
+        world,
+        render,
+        pose,
+        lens,
+        environment,
+    })
+}
+
+fn build_camera(root: &Field) -> Result<(CameraPose, CameraLens), SceneError> {
+    let mut pose = CameraPose::default();
+    let mut lens = CameraLens::default();
+    if let Some(camera) = root.optional("camera") {
+        camera.expect_fields(&[
+            "look_from",
+            "look_at",
+            "view_up",
+            "vertical_fov",
+            "defocus_angle",
+            "focus_distance",
+        ])?;
+        if let Some(look_from) = camera.optional("look_from") {
+            pose.look_from = look_from.point3()?;
+        }
+        if let Some(look_at) = camera.optional("look_at") {
+            pose.look_at = look_at.point3()?;
+        }
+        if let Some(view_up) = camera.optional("view_up") {
+            pose.view_up = view_up.direction()?;
+        }
+        if let Some(vertical_fov) = camera.optional("vertical_fov") {
+            pose.vertical_fov = vertical_fov.number_between(0.0, 180.0)?;
+        }
+
+        // Report problems with the pose against the field that was given, if any.
+        if let Err(error) = pose.validate() {
+            let field = match error {
+                CameraPoseError::NoViewDirection => {
+                    camera.optional("look_at").or(camera.optional("look_from"))
+                }
+                CameraPoseError::ViewUpParallelToViewDirection => {
+                    camera.optional("view_up").or(camera.optional("look_at"))
+                }
+            };
+            return Err(field.as_ref().unwrap_or(&camera).error(&error.to_string()));
+        }
+        if let Some(defocus_angle) = camera.optional("defocus_angle") {
+            lens.defocus_angle = defocus_angle.number_in(0.0, 180.0)?;
+        }
+        if let Some(focus_distance) = camera.optional("focus_distance") {
+            lens.focus_distance = focus_distance.positive_number()?;
+        }
+    }
+
+    Ok((pose, lens))
+}
+
+fn build_render_settings(root: &Field) -> Result<RenderSettings, SceneError> {
+    let Some(render) = root.optional("render") else {
+        return Ok(RenderSettings::default());
+    };
+    render.expect_fields(&[
+        "aspect_ratio",
+        "image_width",
+        "image_height",
+        "samples_per_pixel",
+        "max_depth",
+    ])?;
+
+    let defaults = RenderSettings::default();
+    let image_width = match render.optional("image_width") {
+        Some(field) => field.integer_in(1, u32::MAX as u64)? as u32,
+        None => defaults.image_width(),
+    };
+    let samples_per_pixel = match render.optional("samples_per_pixel") {
+        Some(field) => field.integer_in(1, u32::MAX as u64)? as u32,
+        None => defaults.samples_per_pixel(),
+    };
+    let max_depth = match render.optional("max_depth") {
+        Some(field) => field.integer_in(1, u32::MAX as u64)? as u32,
+        None => defaults.max_depth(),
+    };
+
+    let settings = match (
+        render.optional("image_height"),
+        render.optional("aspect_ratio"),
+    ) {
+        (Some(image_height), Some(_)) => {
+            return Err(image_height.error("cannot be combined with aspect_ratio"))
+        }
+        (Some(image_height), None) => {
+            let image_height = image_height.integer_in(1, u32::MAX as u64)? as u32;
+            RenderSettings::new(image_width, image_height, samples_per_pixel, max_depth)
+        }
+        (None, Some(aspect_ratio)) => RenderSettings::from_aspect_ratio(
+            image_width,
+            aspect_ratio.positive_number()?,
+            samples_per_pixel,
+            max_depth,
+        ),
+        (None, None) => RenderSettings::from_aspect_ratio(
+            image_width,
+            defaults.aspect_ratio(),
+            samples_per_pixel,
+            max_depth,
+        ),
+    };
+    settings.map_err(|error| render.error(&error.to_string()))
+}
+
+fn build_environment(
+    environment: &Field,
+    base_directory: &Path,
+) -> Result<Box<dyn Environment>, SceneError> {
+    let environment_type = environment.required("type")?;
+    match environment_type.string()? {
+        "solid" => {
+            environment.expect_fields(&["type", "color"])?;
+            let color = environment.required("color")?.color()?;
+            Ok(Box::new(SolidColor::new(color)))
+        }
+        "gradient" => {
+            environment.expect_fields(&["type", "bottom", "top"])?;
+            let bottom = environment.required("bottom")?.color()?;
+            let top = environment.required("top")?.color()?;
+            Ok(Box::new(Gradient::new(bottom, top)))
+        }
+        "image" | "light" => {
+            environment.expect_fields(&["type", "path"])?;
+            let path_field = environment.required("path")?;
+            let path = base_directory.join(path_field.string()?);
+            let image = read_image(&path).map_err(|error| {
+                path_field.error(&format!("could not load '{}': {}", path.display(), error))
+            })?;
+            let empty = || path_field.error(&format!("'{}' is empty", path.display()));
+            if environment_type.string()? == "image" {
+                Ok(Box::new(ImageEnvironment::new(image).ok_or_else(empty)?))
+            } else {
+                Ok(Box::new(EnvironmentLight::new(image).ok_or_else(empty)?))
+            }
+        }
+        other => Err(environment_type.error(&format!(
+            "unknown environment type '{}', expected one of: solid, gradient, image, light",
+            other
+        ))),
+    }
+}
+
+fn build_material(material: &Field) -> Result<Box<dyn Material>, SceneError> {
+    let material_type = material.required("type")?;
+    match material_type.string()? {
+        "lambertian" => {
+            material.expect_fields(&["type", "albedo"])?;
+            let albedo = material.required("albedo")?.color()?;
+            Ok(Box::new(Lambertian::new(albedo)))
+        }
+        "metal" => {
+            material.expect_fields(&["type", "albedo", "fuzz"])?;
+            let albedo = material.required("albedo")?.color()?;
+            let fuzz = match material.optional("fuzz") {
+                Some(fuzz) => fuzz.number_in(0.0, 1.0)?,
+                None => 0.0,
+            };
+            Ok(Box::new(Metal::new(albedo, fuzz)))
+        }
+        "dielectric" => {
+            material.expect_fields(&["type", "refraction_index"])?;
+            let refraction_index = material.required("refraction_index")?.positive_number()?;
+            Ok(Box::new(Dielectric::new(refraction_index)))
+        }
+        "diffuse_light" => {
+            material.expect_fields(&["type", "emit"])?;
+            let emit = material.required("emit")?.color()?;
+            Ok(Box::new(DiffuseLight::new(emit)))
+        }
+        other => Err(material_type.error(&format!(
+            "unknown material type '{}', expected one of: lambertian, metal, dielectric, \
+             diffuse_light",
+            other
+        ))),
+    }
+}
+
+fn build_object(
+    object: &Field,
+    materials: &Materials,
+    meshes: &mut HashMap<MeshKey, Arc<Mesh>>,
+    base_directory: &Path,
+    world: &mut HittableList,
+) -> Result<(), SceneError> {
+    let transform = object.optional("transform");
+    let matrix = transform.as_ref().map(build_transform).transpose()?;
+    let not_invertible = || match &transform {
+        Some(transform) => transform.error("the transformation cannot be inverted"),
+        None => object.error("the transformation cannot be inverted"),
+    };
+
+    let object_type = object.required("type")?;
+    let hittable: Box<dyn Hittable> = match object_type.string()? {
+        "sphere" => {
+            object.expect_fields(&["type", "center", "radius", "material", "transform"])?;
+            let center = object.required("center")?.point3()?;
+            let radius = object.required("radius")?.positive_number()?;
+            let material = lookup_material(object, materials)?;
+            Box::new(Sphere::new(center, radius, Box::new(material)))
+        }
+        "triangle" => {
+            object.expect_fields(&[
+                "type",
+                "vertices",
+                "normals",
+                "uvs",
+                "cull_backfaces",
+                "material",
+                "transform",
+            ])?;
+            let [v0, v1, v2] = object.required("vertices")?.three(Field::point3)?;
+            let material = lookup_material(object, materials)?;
+            let mut triangle = Triangle::new(v0, v1, v2, Box::new(material));
+            if let Some(normals) = object.optional("normals") {
+                triangle = triangle.with_normals(normals.three(Field::normal)?);
+            }
+            if let Some(uvs) = object.optional("uvs") {
+                triangle = triangle.with_uvs(uvs.three(Field::uv)?);
+            }
+            if let Some(cull_backfaces) = object.optional("cull_backfaces") {
+                triangle = triangle.with_backface_culling(cull_backfaces.boolean()?);
+            }
+            Box::new(triangle)
+        }
+        "quad" | "disk" => {
+            // A disk is centred on its origin with the edges as semi-axes.
+            object.expect_fields(&["type", "origin", "u", "v", "material", "transform"])?;
+            let shape = match object_type.string()? {
+                "quad" => PlanarShape::Parallelogram,
+                _ => PlanarShape::Disk,
+            };
+            let origin = object.required("origin")?.point3()?;
+            let u = object.required("u")?.direction()?;
+            let v_field = object.required("v")?;
+            let v = v_field.direction()?;
+            let material = lookup_material(object, materials)?;
+            let quad = Quad::with_shape(origin, u, v, shape, Box::new(material));
+            Box::new(quad.ok_or_else(|| v_field.error("edges must not be parallel"))?)
+        }
+        "box" => {
+            object.expect_fields(&["type", "min", "max", "material", "transform"])?;
+            let min = object.required("min")?.point3()?;
+            let max_field = object.required("max")?;
+            let max = max_field.point3()?;
+            let not_above_min = || max_field.error("must be greater than min on every axis");
+            if !(min.x < max.x && min.y < max.y && min.z < max.z) {
+                return Err(not_above_min());
+            }
+            let material = lookup_material(object, materials)?;
+            let sides = make_box(min, max, || Box::new(material.clone()));
+            Box::new(sides.ok_or_else(not_above_min)?)
+        }
+        "mesh" => {
+            object.expect_fields(&["type", "path", "groups", "material", "transform"])?;
+            let path_field = object.required("path")?;
+            let path = base_directory.join(path_field.string()?);
+            let groups = match object.optional("groups") {
+                Some(groups_field) => {
+                    let groups = groups_field
+                        .elements()?
+                        .iter()
+                        .map(Field::string)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if groups.is_empty() {
+                        return Err(groups_field.error("expected at least one group"));
+                    }
+                    Some(groups)
+                }
+                None => None,
+            };
+            let material = match object.optional("material") {
+                Some(material) => Some(material.string()?),
+                None => None,
+            };
+
+            // Objects loading the same model with the same settings share its geometry.
+            let key = (
+                path.clone(),
+                groups
+                    .as_ref()
+                    .map(|groups| groups.iter().map(|name| name.to_string()).collect()),
+                material.map(str::to_string),
+            );
+            let mesh = match meshes.get(&key) {
+                Some(mesh) => mesh.clone(),
+                None => {
+                    let mut data = load_model(&path).map_err(|error| {
+                        path_field.error(&format!("could not load '{}': {}", path.display(), error))
+                    })?;
+                    if let Some(groups) = &groups {
+                        data.retain_groups(groups);
+                    }
+                    // Without an explicit material the mesh keeps the ones from its model file.
+                    if material.is_some() {
+                        data.set_material(Box::new(lookup_material(object, materials)?));
+                    }
+                    if data.faces.is_empty() {
+                        return Err(path_field.error("the model contains no faces"));
+                    }
+                    let mesh = Arc::new(Mesh::new(data));
+                    meshes.insert(key, mesh.clone());
+                    mesh
+                }
+            };
+
+            let instance =
+                Instance::new(mesh, matrix.unwrap_or_default()).ok_or_else(not_invertible)?;
+            world.add(Box::new(instance));
+            return Ok(());
+        }
+        other => {
+            return Err(object_type.error(&format!(
+            "unknown object type '{}', expected one of: sphere, triangle, quad, disk, box, mesh",
+            other
+        )))
+        }
+    };
+
+    match matrix {
+        Some(matrix) => {
+            world.add(Box::new(
+                Transform::new(hittable, matrix).ok_or_else(not_invertible)?,
+            ));
+        }
+        None => world.add(hittable),
+    }
+    Ok(())
+}
+
+// Combines a list of scale, rotate and translate steps, applied in order.
+fn build_transform(transform: &Field) -> Result<Matrix4, SceneError> {
+    let mut matrix = Matrix4::identity();
+    for step in transform.elements()? {
+        let mut operations = step.members()?;
+        let (Some((operation, value)), None) = (operations.next(), operations.next()) else {
+            return Err(step.error("expected an object with a single operation"));
+        };
+        let step_matrix = match operation {
+            "scale" => Matrix4::scaling(value.vector3()?),
+            "rotate" => {
+                value.expect_fields(&["axis", "degrees"])?;
+                let axis = value.required("axis")?.direction()?;
+                let degrees = value.required("degrees")?.number()?;
+                Matrix4::rotation(axis, degrees)
+            }
+            "translate" => Matrix4::translation(value.vector3()?),
+            other => {
+                return Err(value.error(&format!(
+                    "unknown operation '{}', expected one of: scale, rotate, translate",
+                    other
+                )))
+            }
+        };
+        matrix = step_matrix * matrix;
+    }
+    Ok(matrix)
+}
+
+fn lookup_material(object: &Field, materials: &Materials) -> Result<Arc<dyn Material>, SceneError> {
+    let name_field = object.required("material")?;
+    let name = name_field.string()?;
+    match materials.get(name) {
+        Some(material) => Ok(material.clone()),
+        None => Err(name_field.error(&format!("unknown material '{}'", name))),
+    }
+}
+
+// A JSON value together with its path in the scene, used to produce precise error messages.
+struct Field<'a> {
+    path: String,
+    json: &'a Json,
+}
+
+impl<'a> Field<'a> {
+    fn new(path: String, json: &'a Json) -> Self {
+        Self { path, json }
+    }
+
+    fn error(&self, message: &str) -> SceneError {
+        SceneError::Invalid {
+            line: self.json.line,
+            column: self.json.column,
+            message: format!("{}: {}", self.path, message),
+        }
+    }
+
+    fn type_error(&self, expected: &str) -> SceneError {
+        self.error(&format!(
+            "expected {} but found {}",
+            expected,
+            self.json.type_name()
+        ))
+    }
+
+    fn object(&self) -> Result<&'a [(String, Json)], SceneError> {
+        match &self.json.value {
+            JsonValue::Object(members) => Ok(members),
+            _ => Err(self.type_error("an object")),
+        }
+    }
+
+    fn members(&self) -> Result<impl Iterator<Item = (&'a str, Field<'a>)> + '_, SceneError> {
+        Ok(self.object()?.iter().map(|(key, json)| {
+            (
+                key.as_str(),
+                Field::new(format!("{}.{}", self.path, key), json),
+            )
+        }))
+    }
+
+    fn elements(&self) -> Result<Vec<Field<'a>>, SceneError> {
+        match &self.json.value {
+            JsonValue::Array(elements) => Ok(elements
+                .iter()
+                .enumerate()
+                .map(|(i, json)| Field::new(format!("{}[{}]", self.path, i), json))
+                .collect()),
+            _ => Err(self.type_error("an array")),
+        }
+    }
+
+    fn expect_fields(&self, allowed: &[&str]) -> Result<(), SceneError> {
+        for (key, field) in self.members()? {
+            if !allowed.contains(&key) {
+                return Err(field.error(&format!(
+                    "unknown field, expected one of: {}",
+                    allowed.join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn optional(&self, key: &str) -> Option<Field<'a>> {
+        match &self.json.value {
+            JsonValue::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, json)| Field::new(format!("{}.{}", self.path, key), json)),
+            _ => None,
+        }
+    }
+
+    fn required(&self, key: &str) -> Result<Field<'a>, SceneError> {
+        self.object()?;
+        self.optional(key)
+            .ok_or_else(|| self.error(&format!("missing required field '{}'", key)))
+    }
+
+    fn three<T>(
+        &self,
+        element: impl Fn(&Field<'a>) -> Result<T, SceneError>,
+    ) -> Result<[T; 3], SceneError> {
+        match self.elements()?.as_slice() {
+            [a, b, c] => Ok([element(a)?, element(b)?, element(c)?]),
+            _ => Err(self.type_error("an array of three elements")),
+        }
+    }
+
+    fn boolean(&self) -> Result<bool, SceneError> {
+        match self.json.value {
+            JsonValue::Bool(boolean) => Ok(boolean),
+            _ => Err(self.type_error("a boolean")),
+        }
+    }
+
+    fn string(&self) -> Result<&'a str, SceneError> {
+        match &self.json.value {
+            JsonValue::String(string) => Ok(string),
+            _ => Err(self.type_error("a string")),
+        }
+    }
+
+    fn json_number(&self) -> Result<f64, SceneError> {
+        match self.json.value {
+            JsonValue::Number(number) if number.is_finite() => Ok(number),
+            _ => Err(self.type_error("a number")),
+        }
+    }
+
+    fn number(&self) -> Result<Float, SceneError> {
+        self.json_number().map(|number| number as Float)
+    }
+
+    fn positive_number(&self) -> Result<Float, SceneError> {
+        let number = self.number()?;
+        if number > 0.0 {
+            Ok(number)
+        } else {
+            Err(self.error(&format!("expected a positive number but found {}", number)))
+        }
+    }
+
+    fn number_in(&self, min: Float, max: Float) -> Result<Float, SceneError> {
+        let number = self.number()?;
+        if (min..=max).contains(&number) {
+            Ok(number)
+        } else {
+            Err(self.error(&format!(
+                "expected a number between {} and {} but found {}",
+                min, max, number
+            )))
+        }
+    }
+
+    fn number_between(&self, min: Float, max: Float) -> Result<Float, SceneError> {
+        let number = self.number()?;
+        if min < number && number < max {
+            Ok(number)
+        } else {
+            Err(self.error(&format!(
+                "expected a number strictly between {} and {} but found {}",
+                min, max, number
+            )))
+        }
+    }
+
+    fn integer_in(&self, min: u64, max: u64) -> Result<u64, SceneError> {
+        let number = self.json_number()?;
+        if number.fract() == 0.0 && number >= min as f64 && number <= max as f64 {
+            Ok(number as u64)
+        } else {
+            Err(self.error(&format!(
+                "expected a whole number between {} and {} but found {}",
+                min, max, number
+            )))
+        }
+    }
+
+    fn vector3(&self) -> Result<Vector3, SceneError> {
+        match &self.json.value {
+            JsonValue::Array(elements) if elements.len() == 3 => {
+                let component =
+                    |i: usize| Field::new(format!("{}[{}]", self.path, i), &elements[i]).number();
+                Ok(Vector3::new(component(0)?, component(1)?, component(2)?))
+            }
+            _ => Err(self.type_error("an array of three numbers")),
+        }
+    }
+
+    fn point3(&self) -> Result<Point3, SceneError> {
+        let Vector3 { x, y, z } = self.vector3()?;
+        Ok(Point3::new(x, y, z))
+    }
+
+    fn direction(&self) -> Result<Vector3, SceneError> {
+        let direction = self.vector3()?;
+        if direction.is_near_zero() {
+            Err(self.error("direction must not be zero"))
+        } else {
+            Ok(direction)
+        }
+    }
+
+    fn normal(&self) -> Result<Normal3, SceneError> {
+        Ok(Normal3::new(self.direction()?))
+    }
+
+    fn uv(&self) -> Result<(Float, Float), SceneError> {
+        match &self.json.value {
+            JsonValue::Array(elements) if elements.len() == 2 => {
+                let component =
+                    |i: usize| Field::new(format!("{}[{}]", self.path, i), &elements[i]).number();
+                Ok((component(0)?, component(1)?))
+            }
+            _ => Err(self.type_error("an array of two numbers")),
+        }
+    }
+
+    fn color(&self) -> Result<Color, SceneError> {
+        let Vector3 { x, y, z } = self.vector3()?;
+        if x < 0.0 || y < 0.0 || z < 0.0 {
+            Err(self.error("colour components must not be negative"))
+        } else {
+            Ok(Color::new(x, y, z))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(source: &str) -> (usize, usize, String) {
+        match parse_scene(source, Path::new("")) {
+            Err(SceneError::Invalid {
+                line,
+                column,
+                message,
+            }) => (line, column, message),
+            Err(error) => panic!("unexpected error: {error}"),
+            Ok(_) => panic!("scene should have been rejected"),
+        }
+    }
+
+    #[test]
+    fn reports_syntax_errors_at_their_position() {
+        assert_eq!(
+            error("{\n  \"render\": {\"max_depth\": 5,}\n}"),
+            (2, 29, "expected a quoted field name".to_string())
+        );
+    }
+
+    #[test]
+    fn reports_errors_against_the_field_path() {
+        let source = r#"{
+  "materials": {"red": {"type": "lambertian", "albedo": [1, 0, 0]}},
+  "objects": [
+    {"type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "red"},
+    {"type": "sphere", "center": [0, 0, 0], "radius": -1, "material": "red"}
+  ]
+}"#;
+        assert_eq!(
+            error(source),
+            (
+                5,
+                55,
+                "scene.objects[1].radius: expected a positive number but found -1".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn reports_type_errors_with_the_found_type() {
+        let (_, _, message) = error(r#"{"camera": {"look_from": [0, "1", 0]}}"#);
+        assert_eq!(
+            message,
+            "scene.camera.look_from[1]: expected a number but found a string"
+        );
+        let (_, _, message) = error(r#"{"materials": {"m": {"type": "metal", "albedo": true}}}"#);
+        assert_eq!(
+            message,
+            "scene.materials.m.albedo: expected an array of three numbers but found a boolean"
+        );
+    }
+
+    #[test]
+    fn reports_unknown_and_missing_fields() {
+        let (_, _, message) = error(r#"{"render": {"image_widht": 10}}"#);
+        assert!(message.starts_with("scene.render.image_widht: unknown field, expected one of:"));
+
+        let (_, _, message) = error(r#"{"objects": [{"type": "sphere", "radius": 1}]}"#);
+        assert_eq!(message, "scene.objects[0]: missing required field 'center'");
+
+        let (_, _, message) = error(
+            r#"{"objects": [{"type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "x"}]}"#,
+        );
+        assert_eq!(message, "scene.objects[0].material: unknown material 'x'");
+    }
+
+    #[test]
+    fn rejects_boxes_that_are_flat_or_inverted() {
+        for max in ["[1, 0, 1]", "[1, 1, -1]"] {
+            let source = format!(
+                r#"{{"materials": {{"m": {{"type": "lambertian", "albedo": [1, 1, 1]}}}},
+                    "objects": [{{"type": "box", "min": [0, 0, 0], "max": {}, "material": "m"}}]}}"#,
+                max
+            );
+            let (_, _, message) = error(&source);
+            assert_eq!(
+                message,
+                "scene.objects[0].max: must be greater than min on every axis"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_empty_mesh_group_selections() {
+        let (_, _, message) =
+            error(r#"{"objects": [{"type": "mesh", "path": "model.obj", "groups": []}]}"#);
+        assert_eq!(
+            message,
+            "scene.objects[0].groups: expected at least one group"
+        );
+    }
+
+    #[test]
+    fn rejects_camera_looking_at_its_own_position() {
+        let (line, column, message) =
+            error(r#"{"camera": {"look_from": [1, 2, 3], "look_at": [1, 2, 3]}}"#);
+        assert_eq!((line, column), (1, 48));
+        assert_eq!(
+            message,
+            "scene.camera.look_at: look_from and look_at must be different points"
+        );
+    }
+
+    #[test]
+    fn rejects_view_up_parallel_to_view_direction() {
+        let (_, _, message) = error(
+            r#"{"camera": {"look_from": [0, 0, 0], "look_at": [0, 5, 0], "view_up": [0, -2, 0]}}"#,
+        );
+        assert_eq!(
+            message,
+            "scene.camera.view_up: view_up must not be parallel to the view direction"
+        );
+
+        // The default view_up is checked against the given view direction too.
+        let (_, _, message) = error(r#"{"camera": {"look_at": [0, 1, 0]}}"#);
+        assert!(message.starts_with("scene.camera.look_at: view_up"));
+    }
+
+    #[test]
+    fn rejects_vertical_fov_at_the_limits() {
+        for fov in ["0", "180", "-10", "200"] {
+            let source = format!(r#"{{"camera": {{"vertical_fov": {}}}}}"#, fov);
+            let (_, _, message) = error(&source);
+            assert!(
+                message.starts_with("scene.camera.vertical_fov: expected a number strictly"),
+                "{message}"
+            );
+        }
+        assert!(parse_scene(r#"{"camera": {"vertical_fov": 179.5}}"#, Path::new("")).is_ok());
+    }
+}
@@ -1,30 +1,42 @@
+use crate::aabb::Aabb;
+use crate::float::consts::PI;
+use crate::float::Float;
 use crate::hit::{HitRecord, Hittable};
 use crate::interval::Interval;
 use crate::material::Material;
+use crate::normal::Normal3;
+use crate::point::Point3;
 use crate::ray::Ray;
 use crate::vector::{dot, Vector3};
 
 pub struct Sphere {
-    center: Vector3,
-    radius: f64,
+    center: Point3,
+    radius: Float,
     material: Box<dyn Material>,
+    bbox: Aabb,
 }
 
 impl Sphere {
-    pub fn new(center: Vector3, radius: f64, material: Box<dyn Material>) -> Self {
+    pub fn new(center: Point3, radius: Float, material: Box<dyn Material>) -> Self {
+        // A negative radius keeps its sign so the normals point inward, which models the inner
+        // surface of a hollow sphere; the bounds only depend on its size.
+        let extent = radius.abs();
+        let radius_vector = Vector3::new(extent, extent, extent);
+        let bbox = Aabb::from_points(center - radius_vector, center + radius_vector);
         Self {
             center,
             radius,
             material,
+            bbox,
         }
     }
 }
 
 impl Hittable for Sphere {
-    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
-        let oc = &self.center - &ray.origin;
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        let oc = self.center - ray.origin;
         let a = ray.direction.length_squared();
-        let h = dot(&ray.direction, &oc);
+        let h = dot(ray.direction, oc);
         let c = oc.length_squared() - self.radius * self.radius;
 
         let discriminant = h * h - a * c;
@@ -43,15 +55,31 @@ impl Hittable for Sphere {
             }
 
             let point = ray.at(root);
-            let outward_normal = (&point - &self.center) / self.radius;
+            let outward_normal = Normal3::new((point - self.center) / self.radius);
+
+            let uv = sphere_uv(outward_normal);
 
             Some(HitRecord::new(
                 point,
                 root,
+                uv,
                 outward_normal,
-                &ray.direction,
+                ray.direction,
                 &*self.material,
             ))
         }
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox.clone()
+    }
+}
+
+// Maps a point on the unit sphere to texture coordinates, with u running around the Y axis
+// starting from -X and v from the bottom pole to the top.
+fn sphere_uv(normal: Normal3) -> (Float, Float) {
+    let point = normal.to_vector();
+    let theta = (-point.y).clamp(-1.0, 1.0).acos();
+    let phi = (-point.z).atan2(point.x) + PI;
+    (phi / (2.0 * PI), theta / PI)
 }
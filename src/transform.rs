@@ -0,0 +1,144 @@
+use crate::aabb::{Aabb, EMPTY_BOX};
+use crate::hit::{HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::matrix::{Matrix3, Matrix4};
+use crate::normal::Normal3;
+use crate::point::Point3;
+use crate::ray::Ray;
+use std::sync::Arc;
+
+// Places an object in the world with an arbitrary affine transformation. Rays are moved into
+// object space for the intersection and the hit is moved back.
+pub struct Transform {
+    object: Box<dyn Hittable>,
+    placement: Placement,
+}
+
+impl Transform {
+    // Returns `None` when the matrix is not affine or cannot be inverted, e.g. when it scales an
+    // axis to zero.
+    pub fn new(object: Box<dyn Hittable>, matrix: Matrix4) -> Option<Self> {
+        let placement = Placement::new(matrix, &object.bounding_box())?;
+        Some(Self { object, placement })
+    }
+}
+
+impl Hittable for Transform {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        self.placement.hit(&*self.object, ray, ray_t)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.placement.bbox.clone()
+    }
+}
+
+// A transformed reference to an object that may be placed many times, such as a large mesh,
+// without duplicating its geometry.
+pub struct Instance {
+    object: Arc<dyn Hittable>,
+    placement: Placement,
+}
+
+impl Instance {
+    // Returns `None` when the matrix is not affine or cannot be inverted.
+    pub fn new(object: Arc<dyn Hittable>, matrix: Matrix4) -> Option<Self> {
+        let placement = Placement::new(matrix, &object.bounding_box())?;
+        Some(Self { object, placement })
+    }
+}
+
+impl Hittable for Instance {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        self.placement.hit(&*self.object, ray, ray_t)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.placement.bbox.clone()
+    }
+}
+
+struct Placement {
+    matrix: Matrix4,
+    inverse: Matrix4,
+    normal_matrix: Matrix3,
+    bbox: Aabb,
+}
+
+impl Placement {
+    fn new(matrix: Matrix4, object_bbox: &Aabb) -> Option<Self> {
+        // A projective bottom row would need a perspective divide, which rays cannot express.
+        if matrix.rows[3] != [0.0, 0.0, 0.0, 1.0] {
+            return None;
+        }
+        let inverse = matrix.inverse()?;
+        let normal_matrix = matrix.linear().normal_matrix()?;
+        let bbox = transform_bounding_box(&matrix, object_bbox);
+        Some(Self {
+            matrix,
+            inverse,
+            normal_matrix,
+            bbox,
+        })
+    }
+
+    // The object-space direction is left unnormalized, so hits keep the same ray parameter.
+    fn hit<'obj>(
+        &self,
+        object: &'obj dyn Hittable,
+        ray: &Ray,
+        ray_t: Interval,
+    ) -> Option<HitRecord<'obj>> {
+        let object_ray = Ray::new(
+            self.inverse.transform_point(ray.origin),
+            self.inverse.transform_vector(ray.direction),
+        );
+
+        let mut hit_record = object.hit(&object_ray, ray_t)?;
+        hit_record.point = self.matrix.transform_point(hit_record.point);
+        hit_record.normal =
+            Normal3::new(self.normal_matrix.transform(hit_record.normal.to_vector()));
+        Some(hit_record)
+    }
+}
+
+// Bounds the transformed corners of the object's box.
+fn transform_bounding_box(matrix: &Matrix4, bbox: &Aabb) -> Aabb {
+    if bbox.x.min > bbox.x.max || bbox.y.min > bbox.y.max || bbox.z.min > bbox.z.max {
+        return EMPTY_BOX;
+    }
+
+    let mut transformed = EMPTY_BOX;
+    for corner in 0..8 {
+        let x = [bbox.x.min, bbox.x.max][corner & 1];
+        let y = [bbox.y.min, bbox.y.max][(corner >> 1) & 1];
+        let z = [bbox.z.min, bbox.z.max][(corner >> 2) & 1];
+        let point = matrix.transform_point(Point3::new(x, y, z));
+        transformed = Aabb::surrounding(&transformed, &Aabb::from_points(point, point));
+    }
+    transformed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::material::Lambertian;
+    use crate::sphere::Sphere;
+    use crate::vector::Vector3;
+
+    fn sphere() -> Box<dyn Hittable> {
+        let material = Box::new(Lambertian::new(Color::gray(0.5)));
+        Box::new(Sphere::new(Point3::origin(), 1.0, material))
+    }
+
+    #[test]
+    fn rejects_projective_matrices() {
+        let mut matrix = Matrix4::translation(Vector3::new(1.0, 2.0, 3.0));
+        assert!(Transform::new(sphere(), matrix.clone()).is_some());
+
+        matrix.rows[3] = [0.0, 0.0, 1.0, 1.0];
+        assert!(Transform::new(sphere(), matrix.clone()).is_none());
+        assert!(Instance::new(Arc::from(sphere()), matrix).is_none());
+    }
+}
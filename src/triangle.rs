@@ -0,0 +1,310 @@
+use crate::aabb::Aabb;
+use crate::color::Color;
+use crate::float::Float;
+use crate::hit::{HitRecord, Hittable};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::normal::Normal3;
+use crate::point::Point3;
+use crate::ray::Ray;
+use crate::vector::{cross, dot};
+
+pub struct Triangle {
+    vertices: [Point3; 3],
+    normals: Option<[Normal3; 3]>,
+    uvs: Option<[(Float, Float); 3]>,
+    material: Box<dyn Material>,
+    cull_backfaces: bool,
+    bbox: Aabb,
+}
+
+impl Triangle {
+    pub fn new(v0: Point3, v1: Point3, v2: Point3, material: Box<dyn Material>) -> Self {
+        let bbox = Aabb::surrounding(&Aabb::from_points(v0, v1), &Aabb::from_points(v2, v2));
+        Self {
+            vertices: [v0, v1, v2],
+            normals: None,
+            uvs: None,
+            material,
+            cull_backfaces: false,
+            bbox,
+        }
+    }
+
+    // Per-vertex normals are interpolated across the face for smooth shading.
+    pub fn with_normals(mut self, normals: [Normal3; 3]) -> Self {
+        self.normals = Some(normals);
+        self
+    }
+
+    pub fn with_uvs(mut self, uvs: [(Float, Float); 3]) -> Self {
+        self.uvs = Some(uvs);
+        self
+    }
+
+    // Ignores hits from behind, where the vertices appear clockwise.
+    pub fn with_backface_culling(mut self, cull_backfaces: bool) -> Self {
+        self.cull_backfaces = cull_backfaces;
+        self
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        let [v0, v1, v2] = self.vertices;
+        let (t, b1, b2) = intersect_triangle(ray, v0, v1, v2, &ray_t, self.cull_backfaces)?;
+
+        let uv = match &self.uvs {
+            Some(uvs) => interpolate_uv(uvs, b1, b2),
+            None => (b1, b2),
+        };
+        let shading_normals = self
+            .normals
+            .map(|normals| interpolate_normal(normals, b1, b2));
+
+        Some(triangle_hit_record(
+            ray,
+            t,
+            uv,
+            geometric_normal(v0, v1, v2),
+            shading_normals,
+            &*self.material,
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox.clone()
+    }
+}
+
+// Möller–Trumbore ray/triangle intersection. Returns the ray parameter together with the
+// barycentric weights of v1 and v2 at the hit point.
+pub fn intersect_triangle(
+    ray: &Ray,
+    v0: Point3,
+    v1: Point3,
+    v2: Point3,
+    ray_t: &Interval,
+    cull_backfaces: bool,
+) -> Option<(Float, Float, Float)> {
+    let edge1 = v1 - v0;
+    let edge2 = v2 - v0;
+    let p = cross(ray.direction, edge2);
+    let determinant = dot(edge1, p);
+
+    // The determinant scales with the lengths of the edges and the direction, so it is compared
+    // relative to them to treat small and large triangles alike.
+    let scale = edge1.length_squared() * edge2.length_squared() * ray.direction.length_squared();
+    let is_parallel = determinant * determinant <= Float::EPSILON * Float::EPSILON * scale;
+    if is_parallel || (cull_backfaces && determinant < 0.0) {
+        return None;
+    }
+
+    let inverse_determinant = 1.0 / determinant;
+    let s = ray.origin - v0;
+    let b1 = dot(s, p) * inverse_determinant;
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+
+    let q = cross(s, edge1);
+    let b2 = dot(ray.direction, q) * inverse_determinant;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+
+    let t = dot(edge2, q) * inverse_determinant;
+    if !ray_t.surrounds(t) {
+        return None;
+    }
+
+    Some((t, b1, b2))
+}
+
+pub fn geometric_normal(v0: Point3, v1: Point3, v2: Point3) -> Normal3 {
+    Normal3::new(cross(v1 - v0, v2 - v0))
+}
+
+pub fn interpolate_normal(normals: [Normal3; 3], b1: Float, b2: Float) -> Normal3 {
+    let b0 = 1.0 - b1 - b2;
+    let [n0, n1, n2] = normals.map(Normal3::to_vector);
+    Normal3::new(b0 * n0 + b1 * n1 + b2 * n2)
+}
+
+pub fn interpolate_color(colors: [Color; 3], b1: Float, b2: Float) -> Color {
+    let b0 = 1.0 - b1 - b2;
+    b0 * colors[0] + b1 * colors[1] + b2 * colors[2]
+}
+
+pub fn interpolate_uv(uvs: &[(Float, Float); 3], b1: Float, b2: Float) -> (Float, Float) {
+    let b0 = 1.0 - b1 - b2;
+    (
+        b0 * uvs[0].0 + b1 * uvs[1].0 + b2 * uvs[2].0,
+        b0 * uvs[0].1 + b1 * uvs[1].1 + b2 * uvs[2].1,
+    )
+}
+
+// The geometric normal decides which side was hit, so interpolated normals never flip a face
+// around; the shading normal is then turned to that same side.
+pub fn triangle_hit_record<'obj>(
+    ray: &Ray,
+    t: Float,
+    uv: (Float, Float),
+    geometric_normal: Normal3,
+    shading_normal: Option<Normal3>,
+    material: &'obj dyn Material,
+) -> HitRecord<'obj> {
+    let mut record = HitRecord::new(ray.at(t), t, uv, geometric_normal, ray.direction, material);
+    if let Some(shading_normal) = shading_normal {
+        record.normal = shading_normal.face_towards(record.normal);
+    }
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vector::Vector3;
+
+    fn vertices() -> [Point3; 3] {
+        [
+            Point3::origin(),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+        ]
+    }
+
+    fn intersect(
+        origin: Point3,
+        direction: Vector3,
+        cull_backfaces: bool,
+    ) -> Option<(Float, Float, Float)> {
+        let [v0, v1, v2] = vertices();
+        let ray = Ray::new(origin, direction);
+        intersect_triangle(
+            &ray,
+            v0,
+            v1,
+            v2,
+            &Interval::new(0.001, Float::INFINITY),
+            cull_backfaces,
+        )
+    }
+
+    fn towards_front(x: Float, y: Float) -> Option<(Float, Float, Float)> {
+        intersect(Point3::new(x, y, 2.0), Vector3::new(0.0, 0.0, -1.0), false)
+    }
+
+    fn assert_close(actual: Float, expected: Float) {
+        assert!(
+            (actual - expected).abs() < 1e-5,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn hits_inside_with_barycentric_weights() {
+        let (t, b1, b2) = towards_front(0.25, 0.5).unwrap();
+        assert_close(t, 2.0);
+        assert_close(b1, 0.25);
+        assert_close(b2, 0.5);
+    }
+
+    #[test]
+    fn misses_outside_each_edge() {
+        assert!(towards_front(-0.1, 0.5).is_none());
+        assert!(towards_front(0.5, -0.1).is_none());
+        assert!(towards_front(0.6, 0.6).is_none());
+    }
+
+    #[test]
+    fn hits_on_edges_and_vertices() {
+        for (x, y) in [
+            (0.5, 0.0),
+            (0.0, 0.5),
+            (0.5, 0.5),
+            (0.0, 0.0),
+            (1.0, 0.0),
+            (0.0, 1.0),
+        ] {
+            assert!(towards_front(x, y).is_some(), "missed ({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn misses_parallel_rays() {
+        let hit = intersect(
+            Point3::new(-1.0, 0.25, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            false,
+        );
+        assert!(hit.is_none());
+    }
+
+    #[test]
+    fn misses_triangles_behind_the_ray_or_outside_the_interval() {
+        let hit = intersect(
+            Point3::new(0.25, 0.25, 2.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            false,
+        );
+        assert!(hit.is_none());
+
+        let [v0, v1, v2] = vertices();
+        let ray = Ray::new(Point3::new(0.25, 0.25, 2.0), Vector3::new(0.0, 0.0, -1.0));
+        assert!(intersect_triangle(&ray, v0, v1, v2, &Interval::new(0.001, 1.5), false).is_none());
+    }
+
+    #[test]
+    fn culls_back_faces_only_when_asked() {
+        let origin = Point3::new(0.25, 0.25, -2.0);
+        let direction = Vector3::new(0.0, 0.0, 1.0);
+        assert!(intersect(origin, direction, false).is_some());
+        assert!(intersect(origin, direction, true).is_none());
+
+        let front = intersect(Point3::new(0.25, 0.25, 2.0), -direction, true);
+        assert!(front.is_some());
+    }
+
+    #[test]
+    fn hits_small_triangles() {
+        // Their determinant is far below `Float::EPSILON`, which single precision used to reject.
+        for (corner, size) in [
+            (Point3::origin(), 1e-4),
+            (Point3::new(500.0, -300.0, 200.0), 0.05),
+        ] {
+            let triangle = Triangle::new(
+                corner,
+                corner + Vector3::new(size, 0.0, 0.0),
+                corner + Vector3::new(0.0, size, 0.0),
+                Box::new(Lambertian::new(Color::gray(0.5))),
+            );
+            let target = corner + Vector3::new(size / 4.0, size / 4.0, 0.0);
+            let ray = Ray::new(
+                target + Vector3::new(0.0, 0.0, 1.0),
+                Vector3::new(0.0, 0.0, -1.0),
+            );
+
+            let record = triangle.hit(&ray, Interval::new(ray.min_t(), Float::INFINITY));
+            let record = record.unwrap_or_else(|| panic!("missed triangle of size {size}"));
+            assert!((record.t - 1.0).abs() < 1e-3);
+            assert!((record.u - 0.25).abs() < 0.01 && (record.v - 0.25).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn shading_normals_face_the_hit_side() {
+        let [v0, v1, v2] = vertices();
+        let tilted = Normal3::new(Vector3::new(0.1, 0.0, 1.0));
+        let triangle = Triangle::new(v0, v1, v2, Box::new(Lambertian::new(Color::gray(0.5))))
+            .with_normals([tilted; 3]);
+
+        let ray = Ray::new(Point3::new(0.25, 0.25, -2.0), Vector3::new(0.0, 0.0, 1.0));
+        let record = triangle
+            .hit(&ray, Interval::new(0.001, Float::INFINITY))
+            .unwrap();
+        assert!(!record.is_front_face);
+        assert!(record.normal.dot(ray.direction) < 0.0);
+    }
+}
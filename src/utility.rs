@@ -1,15 +1,24 @@
-use rand::rngs::ThreadRng;
-use rand::Rng;
-use std::f64::consts::PI;
+use crate::float::consts::PI;
+use crate::float::Float;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use std::cell::RefCell;
 use std::ops::Range;
 
-pub const CLOSEST_TO_ZERO_TO_ONE_RANGE: Range<f64> = f64::MIN_POSITIVE..1.0;
+pub const CLOSEST_TO_ZERO_TO_ONE_RANGE: Range<Float> = Float::MIN_POSITIVE..1.0;
 
-pub fn degrees_to_radians(degrees: f64) -> f64 {
+thread_local! {
+    static RNG: RefCell<SmallRng> = RefCell::new(SmallRng::from_os_rng());
+}
+
+pub fn degrees_to_radians(degrees: Float) -> Float {
     degrees * PI / 180.0
 }
 
-pub fn random_from_range(range: Range<f64>) -> f64 {
-    let mut rng = ThreadRng::default();
-    rng.random_range(range.clone())
+pub fn seed_thread_rng(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = SmallRng::seed_from_u64(seed));
+}
+
+pub fn random_from_range(range: Range<Float>) -> Float {
+    RNG.with(|rng| rng.borrow_mut().random_range(range))
 }
@@ -1,12 +1,14 @@
+use crate::float::Float;
+use crate::normal::Normal3;
 use crate::utility::{random_from_range, CLOSEST_TO_ZERO_TO_ONE_RANGE};
 use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
-use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Range, Sub, SubAssign};
+use std::ops::{Add, AddAssign, Div, DivAssign, Index, Mul, MulAssign, Neg, Range, Sub, SubAssign};
 
-pub fn dot(v1: &Vector3, v2: &Vector3) -> f64 {
+pub fn dot(v1: Vector3, v2: Vector3) -> Float {
     v1.x * v2.x + v1.y * v2.y + v1.z * v2.z
 }
 
-pub fn cross(v1: &Vector3, v2: &Vector3) -> Vector3 {
+pub fn cross(v1: Vector3, v2: Vector3) -> Vector3 {
     Vector3::new(
         v1.y * v2.z - v1.z * v2.y,
         v1.z * v2.x - v1.x * v2.z,
@@ -14,7 +16,7 @@ pub fn cross(v1: &Vector3, v2: &Vector3) -> Vector3 {
     )
 }
 
-pub fn unit_vector(v: &Vector3) -> Vector3 {
+pub fn unit_vector(v: Vector3) -> Vector3 {
     v / v.length()
 }
 
@@ -22,33 +24,50 @@ pub fn random_unit_vector() -> Vector3 {
     loop {
         let p = Vector3::random_from_range(-1.0..1.0);
         let len_sq = p.length_squared();
-        if 1e-160_f64 < len_sq && len_sq < 1.0 {
-            return p;
+        if Float::MIN_POSITIVE < len_sq && len_sq <= 1.0 {
+            return p / len_sq.sqrt();
         }
     }
 }
 
-pub fn random_on_hemisphere(normal: &Vector3) -> Vector3 {
-    let on_unit_sphere = random_unit_vector();
-    if dot(&on_unit_sphere, normal) > 0.0 {
-        on_unit_sphere
-    } else {
-        -on_unit_sphere
+pub fn random_in_unit_disk() -> Vector3 {
+    loop {
+        let p = Vector3::new(
+            random_from_range(-1.0..1.0),
+            random_from_range(-1.0..1.0),
+            0.0,
+        );
+        if p.length_squared() < 1.0 {
+            return p;
+        }
     }
 }
 
-pub fn reflect(v: &Vector3, normal: &Vector3) -> Vector3 {
-    v - 2.0 * dot(&v, &normal) * normal
+pub fn reflect(v: Vector3, normal: Normal3) -> Vector3 {
+    let normal = normal.to_vector();
+    v - 2.0 * dot(v, normal) * normal
 }
 
+pub fn refract(uv: Vector3, normal: Normal3, etai_over_etat: Float) -> Vector3 {
+    let normal = normal.to_vector();
+    let cos_theta = (-dot(uv, normal)).min(1.0);
+    let r_out_perp = etai_over_etat * (uv + cos_theta * normal);
+    let r_out_parallel = -(1.0 - r_out_perp.length_squared()).abs().sqrt() * normal;
+    r_out_perp + r_out_parallel
+}
+
+// Plain-old-data with a fixed field order, so slices of vectors can be handed to SIMD code or
+// reinterpreted as flat `Float` buffers.
+#[derive(Clone, Copy, PartialEq)]
+#[repr(C)]
 pub struct Vector3 {
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
+    pub x: Float,
+    pub y: Float,
+    pub z: Float,
 }
 
 impl Vector3 {
-    pub fn new(x: f64, y: f64, z: f64) -> Self {
+    pub fn new(x: Float, y: Float, z: Float) -> Self {
         Self { x, y, z }
     }
 
@@ -76,7 +95,7 @@ impl Vector3 {
         }
     }
 
-    pub fn random_from_range(range: Range<f64>) -> Self {
+    pub fn random_from_range(range: Range<Float>) -> Self {
         Self {
             x: random_from_range(range.clone()),
             y: random_from_range(range.clone()),
@@ -84,11 +103,11 @@ impl Vector3 {
         }
     }
 
-    pub fn length(&self) -> f64 {
+    pub fn length(&self) -> Float {
         (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
     }
 
-    pub fn length_squared(&self) -> f64 {
+    pub fn length_squared(&self) -> Float {
         self.x * self.x + self.y * self.y + self.z * self.z
     }
 
@@ -124,15 +143,21 @@ impl Default for Vector3 {
     }
 }
 
-impl Clone for Vector3 {
-    fn clone(&self) -> Self {
-        Vector3::new(self.x, self.y, self.z)
+impl From<(Float, Float, Float)> for Vector3 {
+    fn from(tuple: (Float, Float, Float)) -> Vector3 {
+        Vector3::new(tuple.0, tuple.1, tuple.2)
     }
 }
 
-impl From<(f64, f64, f64)> for Vector3 {
-    fn from(tuple: (f64, f64, f64)) -> Vector3 {
-        Vector3::new(tuple.0, tuple.1, tuple.2)
+impl Index<usize> for Vector3 {
+    type Output = Float;
+    fn index(&self, axis: usize) -> &Float {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Vector3 axis out of range: {}", axis),
+        }
     }
 }
 
@@ -143,7 +168,7 @@ impl Neg for Vector3 {
     }
 }
 
-// Consuming operators (move self)
+// Arithmetic operators
 impl Add for Vector3 {
     type Output = Vector3;
     fn add(self, other: Vector3) -> Vector3 {
@@ -158,161 +183,27 @@ impl Sub for Vector3 {
     }
 }
 
-impl Mul for Vector3 {
-    type Output = Vector3;
-    fn mul(self, other: Vector3) -> Vector3 {
-        Vector3::new(self.x * other.x, self.y * other.y, self.z * other.z)
-    }
-}
-
-impl Mul<Vector3> for f64 {
+impl Mul<Vector3> for Float {
     type Output = Vector3;
     fn mul(self, other: Vector3) -> Vector3 {
         Vector3::new(self * other.x, self * other.y, self * other.z)
     }
 }
 
-impl Mul<f64> for Vector3 {
-    type Output = Vector3;
-    fn mul(self, other: f64) -> Vector3 {
-        Vector3::new(self.x * other, self.y * other, self.z * other)
-    }
-}
-
-impl Div for Vector3 {
-    type Output = Vector3;
-    fn div(self, other: Vector3) -> Vector3 {
-        Vector3::new(self.x / other.x, self.y / other.y, self.z / other.z)
-    }
-}
-
-impl Div<Vector3> for f64 {
-    type Output = Vector3;
-    fn div(self, other: Vector3) -> Vector3 {
-        Vector3::new(self / other.x, self / other.y, self / other.z)
-    }
-}
-
-impl Div<f64> for Vector3 {
-    type Output = Vector3;
-    fn div(self, other: f64) -> Vector3 {
-        Vector3::new(self.x / other, self.y / other, self.z / other)
-    }
-}
-
-// Non-consuming operators (borrow self)
-impl Add for &Vector3 {
-    type Output = Vector3;
-    fn add(self, other: &Vector3) -> Vector3 {
-        Vector3::new(self.x + other.x, self.y + other.y, self.z + other.z)
-    }
-}
-
-impl Add<Vector3> for &Vector3 {
-    type Output = Vector3;
-    fn add(self, other: Vector3) -> Vector3 {
-        Vector3::new(self.x + other.x, self.y + other.y, self.z + other.z)
-    }
-}
-
-impl Add<&Vector3> for Vector3 {
-    type Output = Vector3;
-    fn add(self, other: &Vector3) -> Vector3 {
-        Vector3::new(self.x + other.x, self.y + other.y, self.z + other.z)
-    }
-}
-
-impl Sub for &Vector3 {
-    type Output = Vector3;
-    fn sub(self, other: &Vector3) -> Vector3 {
-        Vector3::new(self.x - other.x, self.y - other.y, self.z - other.z)
-    }
-}
-
-impl Sub<Vector3> for &Vector3 {
-    type Output = Vector3;
-    fn sub(self, other: Vector3) -> Vector3 {
-        Vector3::new(self.x - other.x, self.y - other.y, self.z - other.z)
-    }
-}
-
-impl Sub<&Vector3> for Vector3 {
-    type Output = Vector3;
-    fn sub(self, other: &Vector3) -> Vector3 {
-        Vector3::new(self.x - other.x, self.y - other.y, self.z - other.z)
-    }
-}
-
-impl Mul for &Vector3 {
-    type Output = Vector3;
-    fn mul(self, other: &Vector3) -> Vector3 {
-        Vector3::new(self.x * other.x, self.y * other.y, self.z * other.z)
-    }
-}
-
-impl Mul<Vector3> for &Vector3 {
-    type Output = Vector3;
-    fn mul(self, other: Vector3) -> Vector3 {
-        Vector3::new(self.x * other.x, self.y * other.y, self.z * other.z)
-    }
-}
-
-impl Mul<&Vector3> for Vector3 {
-    type Output = Vector3;
-    fn mul(self, other: &Vector3) -> Vector3 {
-        Vector3::new(self.x * other.x, self.y * other.y, self.z * other.z)
-    }
-}
-
-impl Div for &Vector3 {
-    type Output = Vector3;
-    fn div(self, other: &Vector3) -> Vector3 {
-        Vector3::new(self.x / other.x, self.y / other.y, self.z / other.z)
-    }
-}
-
-impl Div<Vector3> for &Vector3 {
-    type Output = Vector3;
-    fn div(self, other: Vector3) -> Vector3 {
-        Vector3::new(self.x / other.x, self.y / other.y, self.z / other.z)
-    }
-}
-
-impl Div<&Vector3> for Vector3 {
+impl Mul<Float> for Vector3 {
     type Output = Vector3;
-    fn div(self, other: &Vector3) -> Vector3 {
-        Vector3::new(self.x / other.x, self.y / other.y, self.z / other.z)
-    }
-}
-
-impl Mul<f64> for &Vector3 {
-    type Output = Vector3;
-    fn mul(self, other: f64) -> Vector3 {
+    fn mul(self, other: Float) -> Vector3 {
         Vector3::new(self.x * other, self.y * other, self.z * other)
     }
 }
 
-impl Mul<&Vector3> for f64 {
-    type Output = Vector3;
-    fn mul(self, other: &Vector3) -> Vector3 {
-        Vector3::new(self * other.x, self * other.y, self * other.z)
-    }
-}
-
-impl Div<f64> for &Vector3 {
+impl Div<Float> for Vector3 {
     type Output = Vector3;
-    fn div(self, other: f64) -> Vector3 {
+    fn div(self, other: Float) -> Vector3 {
         Vector3::new(self.x / other, self.y / other, self.z / other)
     }
 }
 
-impl Div<&Vector3> for f64 {
-    type Output = Vector3;
-    fn div(self, other: &Vector3) -> Vector3 {
-        Vector3::new(self / other.x, self / other.y, self / other.z)
-    }
-}
-
 // Assignment operators
 impl AddAssign for Vector3 {
     fn add_assign(&mut self, other: Vector3) {
@@ -330,32 +221,16 @@ impl SubAssign for Vector3 {
     }
 }
 
-impl MulAssign for Vector3 {
-    fn mul_assign(&mut self, other: Vector3) {
-        self.x *= other.x;
-        self.y *= other.y;
-        self.z *= other.z;
-    }
-}
-
-impl DivAssign for Vector3 {
-    fn div_assign(&mut self, other: Vector3) {
-        self.x /= other.x;
-        self.y /= other.y;
-        self.z /= other.z;
-    }
-}
-
-impl MulAssign<f64> for Vector3 {
-    fn mul_assign(&mut self, other: f64) {
+impl MulAssign<Float> for Vector3 {
+    fn mul_assign(&mut self, other: Float) {
         self.x *= other;
         self.y *= other;
         self.z *= other;
     }
 }
 
-impl DivAssign<f64> for Vector3 {
-    fn div_assign(&mut self, other: f64) {
+impl DivAssign<Float> for Vector3 {
+    fn div_assign(&mut self, other: Float) {
         self.x /= other;
         self.y /= other;
         self.z /= other;